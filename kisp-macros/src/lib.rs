@@ -0,0 +1,209 @@
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::{format_ident, quote};
+use syn::{parse_macro_input, Error, Expr, FnArg, GenericArgument, ItemFn, Lit, LitStr, Meta, Pat, PathArguments, ReturnType, Type};
+
+//#[builtin(name = "str-len")] on a plain rust function generates a `<fn>_builtin()` constructor,
+//arguments are evaluated and converted with FromKisp, the result goes back through IntoKisp
+
+struct Options{
+    name: Option<LitStr>,
+    variadic: bool,
+}
+
+enum Kind{
+    Scope,
+    Required,
+    Optional,
+    Rest(Box<Type>),
+}
+
+struct Param{
+    name: String,
+    ty: Type,
+    kind: Kind,
+}
+
+fn last_segment(ty: &Type) -> Option<&syn::PathSegment> {
+    match ty {
+        Type::Path(p) => p.path.segments.last(),
+        _ => None,
+    }
+}
+
+fn is_named(ty: &Type, name: &str) -> bool {
+    last_segment(ty).is_some_and(|s| s.ident == name)
+}
+
+//T out of Vec<T>
+fn inner_type(ty: &Type) -> Option<Type> {
+    match &last_segment(ty)?.arguments {
+        PathArguments::AngleBracketed(args) => args.args.iter().find_map(|a| match a {
+            GenericArgument::Type(t) => Some(t.clone()),
+            _ => None,
+        }),
+        _ => None,
+    }
+}
+
+fn is_scope(ty: &Type) -> bool {
+    match ty {
+        Type::Reference(r) => is_named(&r.elem, "ScopeRef"),
+        _ => false,
+    }
+}
+
+fn doc_comment(function: &ItemFn) -> Vec<String> {
+    function.attrs.iter()
+        .filter(|a| a.path().is_ident("doc"))
+        .filter_map(|a| match &a.meta {
+            Meta::NameValue(nv) => match &nv.value {
+                Expr::Lit(l) => match &l.lit {
+                    Lit::Str(s) => Some(s.value().trim().to_string()),
+                    _ => None,
+                },
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect()
+}
+
+fn parameters(function: &ItemFn, options: &Options) -> syn::Result<Vec<Param>> {
+    let mut params = Vec::new();
+    let count = function.sig.inputs.len();
+    for (pos, input) in function.sig.inputs.iter().enumerate() {
+        let FnArg::Typed(typed) = input else {
+            return Err(Error::new_spanned(input, "builtins can't take self"));
+        };
+        let name = match typed.pat.as_ref() {
+            Pat::Ident(i) => i.ident.to_string().trim_start_matches('_').to_string(),
+            _ => format!("arg{}", pos),
+        };
+        let ty = typed.ty.as_ref().clone();
+        let kind = if is_scope(&ty) {
+            Kind::Scope
+        } else if options.variadic && pos + 1 == count {
+            let inner = inner_type(&ty).filter(|_| is_named(&ty, "Vec"))
+                .ok_or_else(|| Error::new_spanned(&typed.ty, "the last parameter of a variadic builtin must be a Vec"))?;
+            Kind::Rest(Box::new(inner))
+        } else if is_named(&ty, "Option") {
+            Kind::Optional
+        } else {
+            if params.iter().any(|p: &Param| matches!(p.kind, Kind::Optional)) {
+                return Err(Error::new_spanned(&typed.ty, "required parameters can't follow optional ones"));
+            }
+            Kind::Required
+        };
+        params.push(Param{name, ty, kind});
+    }
+    Ok(params)
+}
+
+fn expand(options: Options, function: ItemFn) -> syn::Result<proc_macro2::TokenStream> {
+    let ident = &function.sig.ident;
+    let vis = &function.vis;
+    let name = options.name.clone()
+        .unwrap_or_else(|| LitStr::new(&ident.to_string().replace('_', "-"), Span::call_site()));
+    let params = parameters(&function, &options)?;
+
+    let min = params.iter().filter(|p| matches!(p.kind, Kind::Required)).count();
+    let optional = params.iter().filter(|p| matches!(p.kind, Kind::Optional)).count();
+    let max = match options.variadic {
+        true => quote!(None),
+        false => {
+            let max = min + optional;
+            quote!(Some(#max))
+        }
+    };
+
+    let mut bindings = Vec::new();
+    let mut call_args = Vec::new();
+    for (pos, param) in params.iter().enumerate() {
+        let var = format_ident!("__arg{}", pos);
+        let ty = &param.ty;
+        bindings.push(match &param.kind {
+            Kind::Scope => quote!(let #var = scope;),
+            Kind::Required => quote!(
+                let #var = ::kisp::value::convert::convert_arg::<#ty>(scope, &values.next().unwrap())?;
+            ),
+            Kind::Optional => quote!(
+                let #var = match values.next() {
+                    Some(v) => ::kisp::value::convert::convert_arg::<#ty>(scope, &v)?,
+                    None => None,
+                };
+            ),
+            Kind::Rest(inner) => quote!(
+                let #var = values
+                    .map(|v| ::kisp::value::convert::convert_arg::<#inner>(scope, &v))
+                    .collect::<Result<#ty, _>>()?;
+            ),
+        });
+        call_args.push(var);
+    }
+
+    let fallible = match &function.sig.output {
+        ReturnType::Type(_, ty) => is_named(ty, "Result"),
+        ReturnType::Default => false,
+    };
+    let call = match fallible {
+        true => quote!(#ident(#(#call_args),*)?),
+        false => quote!(#ident(#(#call_args),*)),
+    };
+
+    let usage: Vec<String> = params.iter()
+        .filter_map(|p| match p.kind {
+            Kind::Scope => None,
+            Kind::Required => Some(p.name.clone()),
+            Kind::Optional => Some(format!("[{}]", p.name)),
+            Kind::Rest(_) => Some(format!("{}...", p.name)),
+        })
+        .collect();
+    let signature = std::iter::once(name.value()).chain(usage).collect::<Vec<String>>().join(" ");
+    let doc = std::iter::once(format!("({})", signature))
+        .chain(doc_comment(&function))
+        .collect::<Vec<String>>()
+        .join("\n");
+
+    let constructor = format_ident!("{}_builtin", ident);
+    Ok(quote!(
+        #function
+
+        #vis fn #constructor() -> ::kisp::value::builtin::BuiltinFunction {
+            ::kisp::value::builtin::BuiltinFunction::new(#name, |scope, _ctx, args| {
+                let values = args.eval_all(scope)?;
+                ::kisp::value::convert::check_arity(scope, #name, values.len(), #min, #max)?;
+                #[allow(unused_mut, unused_variables)]
+                let mut values = values.into_iter();
+                #(#bindings)*
+                let result = #call;
+                Ok((::kisp::value::convert::IntoKisp::into_kisp(result), ::kisp::value::EvalContext::none()))
+            })
+            .with_doc(#doc)
+            .with_arity(#min, #max)
+        }
+    ))
+}
+
+#[proc_macro_attribute]
+pub fn builtin(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let mut options = Options{name: None, variadic: false};
+    if !attr.is_empty() {
+        let parser = syn::meta::parser(|meta| {
+            if meta.path.is_ident("name") {
+                options.name = Some(meta.value()?.parse()?);
+                Ok(())
+            } else if meta.path.is_ident("variadic") {
+                options.variadic = true;
+                Ok(())
+            } else {
+                Err(meta.error("expected `name = \"...\"` or `variadic`"))
+            }
+        });
+        parse_macro_input!(attr with parser);
+    }
+    let function = parse_macro_input!(item as ItemFn);
+    expand(options, function)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
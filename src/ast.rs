@@ -21,10 +21,12 @@ number = "1" / "2" / " ..." / "9"
 
 empty = " "
  */
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum SExpression{
     Symbol(String),
     Number(Numeric),
+    Keyword(String),
+    String(String),
     //DotExpression(Box<SExpression>,Box<SExpression>),
     List(Vec<PosExpression>),
     Block(Vec<PosExpression>),
@@ -36,6 +38,13 @@ pub struct PosExpression{
     pub exp: SExpression
 }
 
+//structural, where an expression was written doesn't matter
+impl PartialEq for PosExpression{
+    fn eq(&self, other: &Self) -> bool {
+        self.exp == other.exp
+    }
+}
+
 fn joined(v: &Vec<PosExpression>) -> String {
     let strings: Vec<String> = v.iter().map(|e| e.exp.to_string()).collect();
     strings.join( " ")
@@ -46,8 +55,60 @@ impl Display for SExpression{
         match self {
             SExpression::Symbol(i) => f.write_str(i.as_str()),
             SExpression::Number(i) => f.write_fmt(format_args!("{}", i)),
+            SExpression::Keyword(k) => f.write_fmt(format_args!("{}{}", lexer::langchars::KEYWORD_PREFIX, k)),
+            SExpression::String(s) => f.write_str(&lexer::escape_string(s)),
             SExpression::List(l) => f.write_fmt(format_args!("{}{}{}", lexer::langchars::PARENTHESIS_OPEN, joined(l), lexer::langchars::PARENTHESIS_CLOSE)),
             SExpression::Block(l) => f.write_fmt(format_args!("{}{}{}", lexer::langchars::BRACKET_OPEN, joined(l), lexer::langchars::BRACKET_CLOSE)),
         }
     }
-}
\ No newline at end of file
+}
+
+pub const REST_MARKER: &str = "&";
+
+//one entry of a parameter block like [a (b 10) :key 0 & rest]
+pub struct ParameterSpec<'a>{
+    pub name: &'a PosExpression,
+    pub default: Option<&'a PosExpression>,
+    pub keyword: bool,
+    pub rest: bool,
+}
+
+impl<'a> ParameterSpec<'a>{
+    pub fn ident(&self) -> &'a str {
+        match &self.name.exp {
+            SExpression::Symbol(s) | SExpression::Keyword(s) => s.as_str(),
+            _ => unreachable!("parameter names are always symbols or keywords"),
+        }
+    }
+}
+
+fn is_symbol(exp: &PosExpression) -> bool {
+    matches!(&exp.exp, SExpression::Symbol(s) if s != REST_MARKER)
+}
+
+//None if the block isn't a well formed parameter list: required, optional, keywords and finally a rest
+pub fn parameter_list(block: &PosExpression) -> Option<Vec<ParameterSpec<'_>>> {
+    let SExpression::Block(entries) = &block.exp else { return None };
+    let mut specs: Vec<ParameterSpec> = Vec::with_capacity(entries.len());
+    let mut iter = entries.iter();
+    while let Some(entry) = iter.next() {
+        let seen_optional = specs.iter().any(|s| s.default.is_some());
+        let seen_keyword = specs.iter().any(|s| s.keyword);
+        let spec = match &entry.exp {
+            SExpression::Symbol(s) if s == REST_MARKER => {
+                let name = iter.next().filter(|n| is_symbol(n))?;
+                if iter.next().is_some() {
+                    return None;
+                }
+                ParameterSpec{name, default: None, keyword: false, rest: true}
+            }
+            SExpression::Symbol(_) if !seen_optional => ParameterSpec{name: entry, default: None, keyword: false, rest: false},
+            SExpression::List(pair) if pair.len() == 2 && is_symbol(&pair[0]) && !seen_keyword =>
+                ParameterSpec{name: &pair[0], default: Some(&pair[1]), keyword: false, rest: false},
+            SExpression::Keyword(_) => ParameterSpec{name: entry, default: Some(iter.next()?), keyword: true, rest: false},
+            _ => return None,
+        };
+        specs.push(spec);
+    }
+    Some(specs)
+}
@@ -0,0 +1,12 @@
+use std::io;
+use std::process::ExitCode;
+
+use kisp::lsp::Server;
+
+fn main() -> io::Result<ExitCode> {
+    let stdin = io::stdin();
+    let stdout = io::stdout();
+    let mut server = Server::new();
+    server.run(&mut stdin.lock(), &mut stdout.lock())?;
+    Ok(if server.shutdown_requested() { ExitCode::SUCCESS } else { ExitCode::FAILURE })
+}
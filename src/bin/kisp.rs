@@ -0,0 +1,210 @@
+use std::io::Read;
+use std::process::ExitCode;
+use std::rc::Rc;
+use std::time::Duration;
+use std::{env, fs, io};
+
+use kisp::formatter::{format_source, FormatOptions};
+use kisp::interpreter::{Interpreter, InterpreterOptions};
+use kisp::profiler::Profiler;
+use kisp::resolver::{check_source, Severity};
+use kisp::stdlib::StdModule;
+
+const USAGE: &str = "usage:
+    kisp fmt [--check] [--width N] [--indent N] [FILE...]
+    kisp check FILE...
+    kisp run [--allow MODULES] [--pure] [--fuel N] [--timeout MS] [--max-list-cells N] [--trace] [--profile] [--folded OUT] FILE
+        MODULES is a comma separated list of input, fs, process, time or all
+        --max-list-cells limits list cells only, strings and other values are not counted
+        --profile prints time and allocations per function, --folded writes stacks for flamegraph tools";
+
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+    match args.split_first() {
+        Some((command, rest)) if command == "fmt" => fmt(rest),
+        Some((command, rest)) if command == "check" && !rest.is_empty() => check(rest),
+        Some((command, rest)) if command == "run" => run(rest),
+        _ => {
+            eprintln!("{}", USAGE);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn parse_number(flag: &str, value: Option<&String>) -> Result<usize, String> {
+    value
+        .and_then(|v| v.parse::<usize>().ok())
+        .ok_or_else(|| format!("{} expects a number", flag))
+}
+
+fn parse_modules(value: Option<&String>) -> Result<Vec<StdModule>, String> {
+    let value = value.ok_or("--allow expects a list of modules")?;
+    if value == "all" {
+        return Ok(StdModule::ALL.to_vec());
+    }
+    value.split(',')
+        .map(|name| StdModule::from_name(name.trim()).ok_or_else(|| format!("unknown module {}", name)))
+        .collect()
+}
+
+fn fmt(args: &[String]) -> ExitCode {
+    let mut options = FormatOptions::default();
+    let mut check = false;
+    let mut files: Vec<&String> = Vec::new();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        let parsed = match arg.as_str() {
+            "--check" => { check = true; Ok(()) }
+            "--width" => parse_number(arg, iter.next()).map(|w| options.width = w),
+            "--indent" => parse_number(arg, iter.next()).map(|i| options.indent = i),
+            _ if arg.starts_with("--") => Err(format!("unknown flag {}", arg)),
+            _ => { files.push(arg); Ok(()) }
+        };
+        if let Err(e) = parsed {
+            eprintln!("{}\n{}", e, USAGE);
+            return ExitCode::FAILURE;
+        }
+    }
+
+    if files.is_empty() {
+        let mut source = String::new();
+        if let Err(e) = io::stdin().read_to_string(&mut source) {
+            eprintln!("could not read stdin: {}", e);
+            return ExitCode::FAILURE;
+        }
+        return match format_source(&source, &options) {
+            Ok(formatted) if check && formatted != source => {
+                eprintln!("stdin is not formatted");
+                ExitCode::FAILURE
+            }
+            Ok(_) if check => ExitCode::SUCCESS,
+            Ok(formatted) => {
+                print!("{}", formatted);
+                ExitCode::SUCCESS
+            }
+            Err(e) => {
+                eprintln!("stdin: {:?}", e);
+                ExitCode::FAILURE
+            }
+        };
+    }
+
+    let mut failed = false;
+    for path in files {
+        let result = fs::read_to_string(path)
+            .map_err(|e| e.to_string())
+            .and_then(|source| format_source(&source, &options)
+                .map(|formatted| (source, formatted))
+                .map_err(|e| format!("{:?}", e))
+            );
+        match result {
+            Ok((source, formatted)) if source == formatted => {}
+            Ok(_) if check => {
+                println!("would reformat {}", path);
+                failed = true;
+            }
+            Ok((_, formatted)) => {
+                if let Err(e) = fs::write(path, formatted) {
+                    eprintln!("{}: {}", path, e);
+                    failed = true;
+                }
+            }
+            Err(e) => {
+                eprintln!("{}: {}", path, e);
+                failed = true;
+            }
+        }
+    }
+    if failed { ExitCode::FAILURE } else { ExitCode::SUCCESS }
+}
+
+fn check(files: &[String]) -> ExitCode {
+    let mut failed = false;
+    for path in files {
+        let source = match fs::read_to_string(path) {
+            Ok(source) => source,
+            Err(e) => {
+                eprintln!("{}: {}", path, e);
+                failed = true;
+                continue;
+            }
+        };
+        match check_source(&source) {
+            Ok(diagnostics) => for d in diagnostics {
+                let severity = match d.severity() {
+                    Severity::Error => { failed = true; "error" }
+                    Severity::Warning => "warning",
+                };
+                println!("{}:{}:{}: {}: {}", path, d.cursor.line(), d.cursor.column(), severity, d);
+            },
+            Err(e) => {
+                println!("{}: error: {}", path, e);
+                failed = true;
+            }
+        }
+    }
+    if failed { ExitCode::FAILURE } else { ExitCode::SUCCESS }
+}
+
+fn run(args: &[String]) -> ExitCode {
+    let mut options = InterpreterOptions::default();
+    let mut file: Option<&String> = None;
+    let mut profile = false;
+    let mut folded: Option<&String> = None;
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        let parsed = match arg.as_str() {
+            "--allow" => parse_modules(iter.next()).map(|modules| for m in modules {
+                if !options.modules.contains(&m) {
+                    options.modules.push(m);
+                }
+            }),
+            "--pure" => { options.modules.retain(|m| StdModule::PURE.contains(m)); Ok(()) }
+            "--fuel" => parse_number(arg, iter.next()).map(|n| options.budget.fuel = Some(n as u64)),
+            "--timeout" => parse_number(arg, iter.next()).map(|ms| options.budget.time = Some(Duration::from_millis(ms as u64))),
+            "--max-list-cells" => parse_number(arg, iter.next()).map(|n| options.budget.list_cells = Some(n)),
+            "--trace" => { options.trace = true; Ok(()) }
+            "--profile" => { profile = true; Ok(()) }
+            "--folded" => iter.next().map(|out| folded = Some(out)).ok_or_else(|| "--folded expects a file".to_string()),
+            _ if arg.starts_with("--") => Err(format!("unknown flag {}", arg)),
+            _ if file.is_none() => { file = Some(arg); Ok(()) }
+            _ => Err("run expects a single file".to_string()),
+        };
+        if let Err(e) = parsed {
+            eprintln!("{}\n{}", e, USAGE);
+            return ExitCode::FAILURE;
+        }
+    }
+    let Some(path) = file else {
+        eprintln!("{}", USAGE);
+        return ExitCode::FAILURE;
+    };
+
+    let interpreter = Interpreter::with_options(options);
+    let profiler = (profile || folded.is_some()).then(|| Rc::new(Profiler::new()));
+    interpreter.set_profiler(profiler.clone());
+    let mut status = match interpreter.eval_file(path) {
+        Ok(_) => ExitCode::SUCCESS,
+        Err(e) => match e.exit_code() {
+            //statuses are a single byte on unix anyway
+            Some(code) => ExitCode::from(code as u8),
+            None => {
+                eprintln!("{}: {}", path, e);
+                ExitCode::FAILURE
+            }
+        },
+    };
+    //a failed run still has a profile up to the failure
+    if let Some(profiler) = profiler {
+        if profile {
+            eprint!("{}", profiler.report());
+        }
+        if let Some(out) = folded {
+            if let Err(e) = fs::write(out, profiler.folded()) {
+                eprintln!("{}: {}", out, e);
+                status = ExitCode::FAILURE;
+            }
+        }
+    }
+    status
+}
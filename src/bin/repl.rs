@@ -1,21 +1,309 @@
-use std::io;
+use std::collections::HashSet;
+use std::io::{self, IsTerminal, Write};
+use std::rc::Rc;
 
-use std::sync::Arc;
-use linefeed::{Interface, ReadResult};
-
-use kisp::lexer::Lexer;
-use kisp::{interpreter, parser};
-use kisp::value;
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::Ordering;
+use std::time::Instant;
+use linefeed::{Completer, Completion, DefaultTerminal, Interface, Prompter, ReadResult, Signal, Terminal};
 
+use kisp::ast::{PosExpression, SExpression};
+use kisp::debugger::{locals, Breakpoint, Frontend, Step, Stepper};
+use kisp::highlight::{highlight, RESET};
+use kisp::interpreter::{eval, Interpreter, InterpreterError, InterpreterOptions};
+use kisp::lexer::{escape_string, open_depth, Lexer, TokenValue};
+use kisp::parser::parse;
+use kisp::profiler::Profiler;
 use kisp::scope::ScopeRef;
-use kisp::value::EvalValue;
+use kisp::stacktrace::StackTrace;
+use kisp::value::error::EvalError;
+use kisp::value::{EvalValue, ReferenceValue};
+use kisp::stdlib::{std_lib_functions, StdModule};
 
 const HISTORY_FILE: &str = ".kisp-history";
+const COMMAND_PREFIX: char = ':';
+const PROMPT: &str = "kisp> ";
+const DEBUG_PROMPT: &str = "debug> ";
+//same width as the prompt, followed by two spaces per open bracket
+const CONTINUATION_PROMPT: &str = "  ... ";
+
+const COMMANDS: [&str; 15] = ["help", "env", "doc", "type", "ast", "tokens", "time", "profile", "debug", "break", "unbreak", "save", "load", "reset", "quit"];
+
+const HELP: &str = "\
+:help           this list
+:env            bindings defined in this session
+:doc <name>     signature and documentation of a function
+:type <expr>    type of the expression's value
+:ast <expr>     parsed syntax tree
+:tokens <expr>  lexer output
+:time <expr>    evaluate and report the time and steps taken
+:profile <expr> evaluate and report the time and allocations per function
+:debug <expr>   evaluate one step at a time
+:break [bp]     pause at a function name or a line of the input, list breakpoints without one
+:unbreak [bp]   remove a breakpoint, all of them without one
+:save <file>    write the session's definitions to a file
+:load <file>    replace the session's definitions with a file's
+:reset          forget everything defined in this session
+:quit           leave the repl";
+
+//the status to leave with if the script called exit
+fn print_result(result: Result<EvalValue, InterpreterError>) -> Option<i32> {
+    match result {
+        Ok(v) => println!("{}", v),
+        Err(e) if e.exit_code().is_some() => return e.exit_code(),
+        Err(InterpreterError::Eval(e)) if matches!(e.error(), EvalError::Interrupted) => println!("Interrupted"),
+        Err(e) => println!("Err: {}", e),
+    }
+    None
+}
+
+fn print_ast(expression: &PosExpression, depth: usize) {
+    let position = format!("{}:{}", expression.cursor.line(), expression.cursor.column());
+    let indent = "  ".repeat(depth);
+    let children = match &expression.exp {
+        SExpression::List(children) => { println!("{}list {}", indent, position); children }
+        SExpression::Block(children) => { println!("{}block {}", indent, position); children }
+        SExpression::Symbol(s) => return println!("{}symbol {} {}", indent, s, position),
+        SExpression::Number(n) => return println!("{}number {} {}", indent, n, position),
+        SExpression::Keyword(k) => return println!("{}keyword {} {}", indent, k, position),
+        SExpression::String(s) => return println!("{}string {} {}", indent, escape_string(s), position),
+    };
+    children.iter().for_each(|c| print_ast(c, depth + 1));
+}
+
+const DEBUG_HELP: &str = "\
+s, step         pause at the next expression
+n, next         pause after this expression
+o, out          pause after the current function returns
+c, continue     run until the next breakpoint
+w, where        current expression and its location
+l, locals       bindings of the current call
+bt, stack       functions being called, innermost first
+p <expr>        evaluate in the current scope
+q, quit         abort the evaluation";
+
+fn print_location(expression: &PosExpression) {
+    println!("at {}:{} {}", expression.cursor.line(), expression.cursor.column(), expression.exp);
+}
+
+//the debug prompt, shown whenever the stepper pauses
+struct ReplFrontend{
+    interface: Arc<Interface<DefaultTerminal>>,
+}
+
+impl Frontend for ReplFrontend{
+    fn paused(&self, scope: &ScopeRef, expression: &PosExpression) -> Result<Step, EvalError> {
+        print_location(expression);
+        let _ = self.interface.set_prompt(DEBUG_PROMPT);
+        let step = loop {
+            let line = match self.interface.read_line() {
+                Ok(ReadResult::Input(line)) => line,
+                Ok(ReadResult::Signal(Signal::Interrupt)) | Ok(ReadResult::Eof) | Err(_) => break Err(EvalError::Interrupted),
+                Ok(ReadResult::Signal(_)) => continue,
+            };
+            let (name, argument) = line.trim().split_once(char::is_whitespace).unwrap_or((line.trim(), ""));
+            match name {
+                //an empty line keeps stepping
+                "" | "s" | "step" => break Ok(Step::In),
+                "n" | "next" => break Ok(Step::Over),
+                "o" | "out" => break Ok(Step::Out),
+                "c" | "continue" => break Ok(Step::Continue),
+                "q" | "quit" => break Err(EvalError::Interrupted),
+                "w" | "where" => print_location(expression),
+                "l" | "locals" => for (name, value) in locals(scope) {
+                    println!("{} = {}", name, value.repr());
+                },
+                "bt" | "stack" => for (depth, name) in StackTrace::from_scope(scope).trace.iter().enumerate() {
+                    println!("#{} {}", depth, name);
+                },
+                "p" | "print" => match parse(&mut Lexer::from_text(argument).into_iter()) {
+                    Ok(ast) => match eval(&ast, Some(scope.clone())).0 {
+                        Ok((value, _)) => println!("{}", value.repr()),
+                        Err(e) => println!("Err: {}", e.error()),
+                    },
+                    Err(e) => println!("Err: Parser: {}", e),
+                },
+                "h" | "help" => println!("{}", DEBUG_HELP),
+                _ => println!("Unknown debugger command {}, try help", name),
+            }
+        };
+        let _ = self.interface.set_prompt(PROMPT);
+        step
+    }
+}
+
+fn signature(value: &EvalValue) -> Option<String> {
+    let EvalValue::Reference(r) = value else { return None };
+    let ReferenceValue::CallableValue(callable) = r.as_ref() else { return None };
+    Some(callable.documentation())
+}
+
+//colon commands, everything else is kisp code. the exit status once the user or the script wants to leave
+fn command(interpreter: &mut Interpreter, stepper: &Stepper<ReplFrontend>, input: &str) -> Option<i32> {
+    let (name, argument) = input.split_once(char::is_whitespace).unwrap_or((input, ""));
+    let argument = argument.trim();
+    match name {
+        "help" => println!("{}", HELP),
+        "quit" | "q" => return Some(0),
+        "reset" => {
+            interpreter.reset();
+            println!("Session cleared");
+        }
+        "env" => for (name, value) in interpreter.scope().local_bindings() {
+            match signature(&value) {
+                Some(signature) => println!("{}", signature.lines().next().unwrap_or_default()),
+                None => println!("{} = {}", name, value.repr()),
+            }
+        },
+        "break" if argument.is_empty() => match stepper.breakpoints() {
+            breakpoints if breakpoints.is_empty() => println!("No breakpoints"),
+            breakpoints => breakpoints.iter().for_each(|b| println!("{}", b)),
+        },
+        "break" => {
+            let breakpoint = Breakpoint::parse(argument);
+            println!("Breakpoint at {}", breakpoint);
+            stepper.add_breakpoint(breakpoint);
+        }
+        "unbreak" if argument.is_empty() => {
+            stepper.clear_breakpoints();
+            println!("Breakpoints cleared");
+        }
+        "unbreak" => match stepper.remove_breakpoint(&Breakpoint::parse(argument)) {
+            true => println!("Breakpoint removed"),
+            false => println!("No breakpoint at {}", Breakpoint::parse(argument)),
+        },
+        "doc" | "type" | "ast" | "tokens" | "time" | "profile" | "debug" | "save" | "load" if argument.is_empty() => {
+            let usage = HELP.lines().find(|l| l.starts_with(&format!(":{} ", name))).unwrap_or_default();
+            println!("usage: {}", usage.split("  ").next().unwrap_or_default());
+        }
+        "doc" => match interpreter.get(argument) {
+            Some(value) => match signature(&value) {
+                Some(signature) => println!("{}", signature),
+                None => println!("{} is a {}, not a function", argument, value.type_name()),
+            },
+            None => println!("{} is not defined", argument),
+        },
+        "type" => match interpreter.eval_str(argument) {
+            Ok(v) => println!("{}", v.type_name()),
+            result => return print_result(result),
+        },
+        "ast" => match parse(&mut Lexer::from_text(argument).into_iter()) {
+            Ok(ast) => print_ast(&ast, 0),
+            Err(e) => println!("Err: Parser: {}", e),
+        },
+        "tokens" => for token in Lexer::from_text(argument).into_iter() {
+            if token.value == TokenValue::EOF {
+                break;
+            }
+            println!("{}:{} {:?}", token.cursor.line(), token.cursor.column(), token.value);
+        },
+        "time" => {
+            let start = Instant::now();
+            let result = interpreter.eval_str(argument);
+            let elapsed = start.elapsed();
+            let exit = print_result(result);
+            println!("took {:?}, {} steps", elapsed, interpreter.scope().runtime.steps());
+            return exit;
+        }
+        "profile" => {
+            let profiler = Rc::new(Profiler::new());
+            interpreter.set_profiler(Some(profiler.clone()));
+            let result = interpreter.eval_str(argument);
+            interpreter.set_profiler(None);
+            let exit = print_result(result);
+            print!("{}", profiler.report());
+            return exit;
+        }
+        "debug" => {
+            stepper.step_in();
+            return print_result(interpreter.eval_str(argument));
+        }
+        "save" => match interpreter.save_session(argument) {
+            Ok(()) => println!("Saved to {}", argument),
+            Err(e) => println!("Err: {}", e),
+        },
+        "load" => match interpreter.load_session(argument) {
+            Ok(()) => println!("Loaded {}", argument),
+            Err(e) => println!("Err: {}", e),
+        },
+        _ => println!("Unknown command :{}, try :help", name),
+    }
+    None
+}
+
+fn prompt_for(pending: &str) -> String {
+    match open_depth(pending) {
+        _ if pending.is_empty() => PROMPT.to_string(),
+        depth => format!("{}{}", CONTINUATION_PROMPT, "  ".repeat(depth)),
+    }
+}
+
+//names the completer offers, refreshed after every input since the interpreter itself can't cross threads
+struct NameCompleter{
+    builtins: Vec<String>,
+    session: Arc<Mutex<Vec<String>>>,
+}
+
+impl<Term: Terminal> Completer<Term> for NameCompleter{
+    fn complete(&self, word: &str, prompter: &Prompter<Term>, start: usize, _end: usize) -> Option<Vec<Completion>> {
+        let line = prompter.buffer();
+        if line[..start].trim().is_empty() && line.trim_start().starts_with(COMMAND_PREFIX) {
+            let word = word.trim_start_matches(COMMAND_PREFIX);
+            return Some(COMMANDS.iter()
+                .filter(|c| c.starts_with(word))
+                .map(|c| Completion::simple(format!("{}{}", COMMAND_PREFIX, c)))
+                .collect());
+        }
+        let session = self.session.lock().unwrap();
+        let mut candidates: Vec<String> = self.builtins.iter().chain(session.iter())
+            .filter(|n| n.starts_with(word))
+            .cloned()
+            .collect();
+        candidates.sort();
+        candidates.dedup();
+        Some(candidates.into_iter().map(Completion::simple).collect())
+    }
+}
+
+//repaints the input just accepted with colours, linefeed can't style the line while it's being edited
+fn repaint(input: &str, prompts: &[String], builtins: &HashSet<String>, session: &[String]) -> io::Result<()> {
+    let columns = DefaultTerminal::new()?.lock_write().size()?.columns;
+    let lines: Vec<&str> = input.lines().collect();
+    //wrapped lines would throw the cursor arithmetic off, leave those alone
+    if lines.len() != prompts.len() || lines.iter().zip(prompts).any(|(l, p)| l.chars().count() + p.chars().count() >= columns) {
+        return Ok(());
+    }
+    let highlighted = highlight(input, |name| builtins.contains(name) || session.iter().any(|s| s == name));
+    let mut out = io::stdout().lock();
+    write!(out, "\x1b[{}A", lines.len())?;
+    for (line, prompt) in highlighted.lines().zip(prompts) {
+        write!(out, "\r\x1b[2K{}{}{}\n", prompt, line, RESET)?;
+    }
+    out.flush()
+}
+
 fn main() -> io::Result<()>{
     let interface = Arc::new(Interface::new("REPL for Kirill's Lisp")?);
     println!("wazzup faggot");
-    interface.set_prompt("kisp> ")?;
-    let mut env: Option<ScopeRef> = None;
+    interface.set_prompt(PROMPT)?;
+    interface.set_report_signal(Signal::Interrupt, true);
+    interface.lock_reader().set_word_break_chars(" \t\n()[]\";");
+
+    let builtins: Vec<String> = std_lib_functions().into_iter().map(|b| b.name).chain(["true".to_string()]).collect();
+    let builtin_set: HashSet<String> = builtins.iter().cloned().collect();
+    let session_names = Arc::new(Mutex::new(Vec::new()));
+    interface.set_completer(Arc::new(NameCompleter{builtins, session: session_names.clone()}));
+    let colour = io::stdout().is_terminal();
+    //the person at the prompt is trusted, give them everything
+    let mut interpreter = Interpreter::with_options(InterpreterOptions{modules: StdModule::ALL.to_vec(), ..Default::default()});
+    let stepper = Rc::new(Stepper::new(ReplFrontend{interface: interface.clone()}));
+    interpreter.set_debugger(Some(stepper.clone()));
+
+    //ctrl-c stops the running evaluation instead of the whole session
+    let interrupt = interpreter.interrupt_handle();
+    if let Err(e) = ctrlc::set_handler(move || interrupt.store(true, Ordering::Relaxed)) {
+        eprintln!("Could not install the Ctrl-C handler: {}", e);
+    }
 
     if let Err(e) = interface.load_history(HISTORY_FILE) {
         if e.kind() == io::ErrorKind::NotFound {
@@ -26,43 +314,75 @@ fn main() -> io::Result<()>{
     }
 
     let mut line_acc: String = String::new();
-    while let ReadResult::Input(line) = interface.read_line()? {
-        line_acc.push_str(line.as_str());
-        if line.ends_with('\\') {
-            continue;
-        }
-        line_acc = line_acc.replace("\\","\n");
-        if !line_acc.trim().is_empty() {
-            interface.add_history_unique(line_acc.clone());
-            let (result, new_env) = do_line(env, line_acc);
-            match result{
-                Ok(v) => {
-                    println!("{}", v);
-                }
-                Err(e) => {
-                    println!("Err: {}", e);
+    let mut prompts: Vec<String> = Vec::new();
+    let mut status = 0;
+    loop {
+        let line = match interface.read_line()? {
+            ReadResult::Input(line) => line,
+            ReadResult::Signal(Signal::Interrupt) => {
+                //drops whatever was typed so far
+                if !line_acc.is_empty() {
+                    println!("Input discarded");
                 }
+                line_acc.clear();
+                prompts.clear();
+                interface.set_prompt(PROMPT)?;
+                continue;
             }
-            line_acc = String::new();
-            env = new_env;
+            ReadResult::Signal(_) => continue,
+            ReadResult::Eof => break,
+        };
+        let pending = !line_acc.is_empty();
+        if pending && line.trim().is_empty() {
+            println!("Input discarded");
+            line_acc.clear();
+            prompts.clear();
+            interface.set_prompt(PROMPT)?;
+            continue;
+        }
+        prompts.push(prompt_for(&line_acc));
+        line_acc.push_str(&line);
+        line_acc.push('\n');
+
+        //commands are always a single line, code continues while brackets are open
+        let is_command = line_acc.trim_start().starts_with(COMMAND_PREFIX);
+        let depth = if is_command { 0 } else { open_depth(&line_acc) };
+        if depth > 0 {
+            interface.set_prompt(&prompt_for(&line_acc))?;
+            continue;
         }
+        interface.set_prompt(PROMPT)?;
 
+        let input = std::mem::take(&mut line_acc);
+        let input_prompts = std::mem::take(&mut prompts);
+        if input.trim().is_empty() {
+            continue;
+        }
+        interface.add_history_unique(input.trim_end().to_string());
+        if let Some(input) = input.trim().strip_prefix(COMMAND_PREFIX) {
+            let exit = command(&mut interpreter, &stepper, input);
+            stepper.reset();
+            if let Some(code) = exit {
+                status = code;
+                break;
+            }
+        } else {
+            if colour {
+                repaint(&input, &input_prompts, &builtin_set, &session_names.lock().unwrap())?;
+            }
+            let exit = print_result(interpreter.eval_str(&input));
+            stepper.reset();
+            if let Some(code) = exit {
+                status = code;
+                break;
+            }
+        }
+        *session_names.lock().unwrap() = interpreter.scope().local_bindings().into_iter().map(|(name, _)| name).collect();
     }
     interface.save_history(HISTORY_FILE)?;
+    //only now, after the history is saved
+    if status != 0 {
+        std::process::exit(status);
+    }
     Ok(() )
 }
-
-fn do_line(env: Option<ScopeRef>, line: String) -> (Result<EvalValue, String>, Option<ScopeRef>) {
-    let lexer = Lexer::from_text(line.as_str());
-    let mut iter = lexer.into_iter();
-    let ast = match parser::parse(&mut iter) {
-        Ok(ast) => ast,
-        Err(e) => return (Err(format!("Parser: {:?}", e)),env),
-    };
-    let (result, modded_env) = interpreter::eval(&ast, env);
-    (
-        result.map_err(|err| format!("Eval: {:?}", err)).map(|v|v.0),
-        Some(modded_env)
-    )
-
-}
\ No newline at end of file
@@ -0,0 +1,229 @@
+use std::cell::{Cell, RefCell};
+use std::fmt;
+use std::fmt::{Display, Formatter};
+
+use crate::ast::PosExpression;
+use crate::scope::ScopeRef;
+use crate::value::{EvalResult, EvalValue};
+use crate::value::error::EvalError;
+
+//hooks the host can install on a runtime to watch evaluation, every method defaults to doing nothing
+pub trait Debugger {
+    //before an expression is evaluated, an error aborts the evaluation
+    fn before(&self, _scope: &ScopeRef, _expression: &PosExpression) -> Result<(), EvalError> {
+        Ok(())
+    }
+
+    fn after(&self, _scope: &ScopeRef, _expression: &PosExpression, _result: &EvalResult) {}
+
+    //a function or lambda was called, its arguments are bound in scope and scope.vararg() has them all
+    fn enter(&self, _scope: &ScopeRef, _name: &str) {}
+
+    fn leave(&self, _scope: &ScopeRef, _name: &str, _result: &EvalResult) {}
+}
+
+impl fmt::Debug for dyn Debugger {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str("<debugger>")
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Breakpoint {
+    Function(String),
+    Line(usize),
+}
+
+impl Breakpoint {
+    //a line number or a function name
+    pub fn parse(text: &str) -> Breakpoint {
+        match text.parse() {
+            Ok(line) => Breakpoint::Line(line),
+            Err(_) => Breakpoint::Function(text.to_string()),
+        }
+    }
+}
+
+impl Display for Breakpoint {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Breakpoint::Function(name) => f.write_fmt(format_args!("function {}", name)),
+            Breakpoint::Line(line) => f.write_fmt(format_args!("line {}", line)),
+        }
+    }
+}
+
+//how to go on after a pause
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Step {
+    //until the next breakpoint
+    Continue,
+    //pause at the very next expression
+    In,
+    //pause once the current expression is done
+    Over,
+    //pause once the current function returned
+    Out,
+}
+
+//what the user interacts with while execution is paused
+pub trait Frontend {
+    //an error aborts the evaluation, EvalError::Interrupted to quit debugging
+    fn paused(&self, scope: &ScopeRef, expression: &PosExpression) -> Result<Step, EvalError>;
+}
+
+//breakpoints and stepping on top of the Debugger hooks, the frontend decides what to do at a pause
+pub struct Stepper<F: Frontend> {
+    frontend: F,
+    breakpoints: RefCell<Vec<Breakpoint>>,
+    mode: Cell<Step>,
+    //expressions currently being evaluated
+    nesting: Cell<usize>,
+    //functions currently being called
+    calls: Cell<usize>,
+    //nesting or call count the step was taken at
+    mark: Cell<usize>,
+    last_line: Cell<usize>,
+    //set by enter, the first expression of the call decides whether a function breakpoint hits
+    entered: RefCell<Option<String>>,
+    //the frontend may evaluate code while paused, that must not pause again
+    paused: Cell<bool>,
+}
+
+impl<F: Frontend> Stepper<F> {
+    pub fn new(frontend: F) -> Stepper<F> {
+        Stepper{
+            frontend,
+            breakpoints: RefCell::new(Vec::new()),
+            mode: Cell::new(Step::Continue),
+            nesting: Cell::new(0),
+            calls: Cell::new(0),
+            mark: Cell::new(0),
+            last_line: Cell::new(0),
+            entered: RefCell::new(None),
+            paused: Cell::new(false),
+        }
+    }
+
+    pub fn frontend(&self) -> &F {
+        &self.frontend
+    }
+
+    pub fn add_breakpoint(&self, breakpoint: Breakpoint) {
+        let mut breakpoints = self.breakpoints.borrow_mut();
+        if !breakpoints.contains(&breakpoint) {
+            breakpoints.push(breakpoint);
+        }
+    }
+
+    //false if there was no such breakpoint
+    pub fn remove_breakpoint(&self, breakpoint: &Breakpoint) -> bool {
+        let mut breakpoints = self.breakpoints.borrow_mut();
+        let before = breakpoints.len();
+        breakpoints.retain(|b| b != breakpoint);
+        breakpoints.len() != before
+    }
+
+    pub fn clear_breakpoints(&self) {
+        self.breakpoints.borrow_mut().clear();
+    }
+
+    pub fn breakpoints(&self) -> Vec<Breakpoint> {
+        self.breakpoints.borrow().clone()
+    }
+
+    //the next evaluation pauses at its first expression
+    pub fn step_in(&self) {
+        self.mode.set(Step::In);
+    }
+
+    //forgets the state of an evaluation that was aborted, breakpoints stay
+    pub fn reset(&self) {
+        self.mode.set(Step::Continue);
+        self.nesting.set(0);
+        self.calls.set(0);
+        self.last_line.set(0);
+        self.entered.replace(None);
+    }
+
+    fn hits_breakpoint(&self, expression: &PosExpression) -> bool {
+        let line = expression.cursor.line();
+        let new_line = self.last_line.replace(line) != line;
+        let entered = self.entered.take();
+        self.breakpoints.borrow().iter().any(|b| match b {
+            Breakpoint::Function(name) => entered.as_deref() == Some(name.as_str()),
+            Breakpoint::Line(l) => new_line && *l == line,
+        })
+    }
+}
+
+impl<F: Frontend> Debugger for Stepper<F> {
+    fn before(&self, scope: &ScopeRef, expression: &PosExpression) -> Result<(), EvalError> {
+        if self.paused.get() {
+            return Ok(());
+        }
+        let level = self.nesting.get();
+        self.nesting.set(level + 1);
+        let stepped = match self.mode.get() {
+            Step::Continue => false,
+            Step::In => true,
+            Step::Over => level <= self.mark.get(),
+            Step::Out => self.calls.get() < self.mark.get(),
+        };
+        //always checked so the line and call tracking stay current
+        let hit = self.hits_breakpoint(expression);
+        if !stepped && !hit {
+            return Ok(());
+        }
+        self.paused.set(true);
+        let step = self.frontend.paused(scope, expression);
+        self.paused.set(false);
+        let step = step?;
+        self.mode.set(step);
+        self.mark.set(match step {
+            Step::Out => self.calls.get(),
+            _ => level,
+        });
+        Ok(())
+    }
+
+    fn after(&self, _scope: &ScopeRef, _expression: &PosExpression, _result: &EvalResult) {
+        if !self.paused.get() {
+            self.nesting.set(self.nesting.get().saturating_sub(1));
+        }
+    }
+
+    fn enter(&self, _scope: &ScopeRef, name: &str) {
+        if !self.paused.get() {
+            self.calls.set(self.calls.get() + 1);
+            self.entered.replace(Some(name.to_string()));
+        }
+    }
+
+    fn leave(&self, _scope: &ScopeRef, _name: &str, _result: &EvalResult) {
+        if !self.paused.get() {
+            self.calls.set(self.calls.get().saturating_sub(1));
+        }
+    }
+}
+
+//bindings visible inside the current call, innermost first, the globals are left out
+pub fn locals(scope: &ScopeRef) -> Vec<(String, EvalValue)> {
+    let mut bindings: Vec<(String, EvalValue)> = Vec::new();
+    let mut current = Some(scope);
+    while let Some(s) = current {
+        if s.parent.is_none() {
+            break;
+        }
+        for (name, value) in s.local_bindings() {
+            if !bindings.iter().any(|(n, _)| *n == name) {
+                bindings.push((name, value));
+            }
+        }
+        if s.is_call() {
+            break;
+        }
+        current = s.parent.as_ref();
+    }
+    bindings
+}
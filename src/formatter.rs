@@ -0,0 +1,261 @@
+use crate::lexer::{langchars, Cursor, Lexer, Token, TokenStream, TokenValue};
+use crate::parser::ParserError;
+
+//canonical layout for kisp source, comments survive the round trip
+
+pub struct FormatOptions{
+    pub width: usize,
+    pub indent: usize,
+}
+
+impl Default for FormatOptions{
+    fn default() -> Self {
+        FormatOptions{width: 80, indent: 4}
+    }
+}
+
+enum Node{
+    Atom(String),
+    //trailing comments share the line with whatever came before them
+    Comment{text: String, trailing: bool},
+    Group{open: char, close: char, children: Vec<Item>},
+}
+
+struct Item{
+    node: Node,
+    blank_before: bool,
+}
+
+struct Reader<'a>{
+    source: Vec<char>,
+    tokens: TokenStream<'a>,
+    last_line: usize,
+}
+
+impl<'a> Reader<'a>{
+    fn atom_text(&self, token: &Token) -> String {
+        match &token.value {
+            TokenValue::Identifier(i) => i.clone(),
+            _ => {
+                let start = token.cursor.abs_position();
+                let len = token.cursor.reach().unwrap_or(0);
+                self.source[start..start+len].iter().collect()
+            }
+        }
+    }
+
+    fn read_items(&mut self, close: Option<(TokenValue, Cursor)>) -> Result<Vec<Item>, ParserError> {
+        let mut items: Vec<Item> = Vec::new();
+        loop {
+            let token = self.tokens.next().unwrap();
+            let line = token.cursor.line();
+            let blank_before = !items.is_empty() && line > self.last_line + 1;
+            let node = match token.value {
+                TokenValue::EOF if close.is_none() => return Ok(items),
+                TokenValue::EOF => return Err(ParserError::UnclosedParenthesis(close.unwrap().1)),
+                TokenValue::UnterminatedString => return Err(ParserError::UnterminatedString(token.cursor)),
+                TokenValue::ParenthesisClose | TokenValue::BracketClose => {
+                    self.last_line = line;
+                    return match &close {
+                        Some((c, _)) if *c == token.value => Ok(items),
+                        _ => Err(ParserError::NoMatchingParser(token.cursor)),
+                    };
+                }
+                TokenValue::Comment(ref text) => Node::Comment{
+                    text: text.trim_end().to_string(),
+                    trailing: line == self.last_line,
+                },
+                TokenValue::ParenthesisOpen => {
+                    self.last_line = line;
+                    let children = self.read_items(Some((TokenValue::ParenthesisClose, token.cursor)))?;
+                    Node::Group{open: langchars::PARENTHESIS_OPEN, close: langchars::PARENTHESIS_CLOSE, children}
+                }
+                TokenValue::BracketOpen => {
+                    self.last_line = line;
+                    let children = self.read_items(Some((TokenValue::BracketClose, token.cursor)))?;
+                    Node::Group{open: langchars::BRACKET_OPEN, close: langchars::BRACKET_CLOSE, children}
+                }
+                _ => Node::Atom(self.atom_text(&token)),
+            };
+            if !matches!(node, Node::Group{..}) {
+                self.last_line = line;
+            }
+            items.push(Item{node, blank_before});
+        }
+    }
+}
+
+fn flat(node: &Node) -> Option<String> {
+    match node {
+        Node::Atom(a) => Some(a.clone()),
+        Node::Comment{..} => None,
+        Node::Group{open, close, children} => {
+            let inner = children.iter()
+                .map(|i| flat(&i.node))
+                .collect::<Option<Vec<String>>>()?;
+            Some(format!("{}{}{}", open, inner.join(" "), close))
+        }
+    }
+}
+
+fn is_group(node: &Node) -> bool {
+    matches!(node, Node::Group{..})
+}
+
+//blocks holding several forms read better one form per line, even if they'd fit
+fn forced_break(node: &Node) -> bool {
+    match node {
+        Node::Group{open: langchars::BRACKET_OPEN, children, ..} =>
+            children.iter().filter(|i| is_group(&i.node)).count() > 1,
+        Node::Group{children, ..} => children.iter().any(|i| forced_break(&i.node)),
+        _ => false,
+    }
+}
+
+//how many leading children of a special form stay on the opening line
+fn header_len(children: &[Item]) -> Option<usize> {
+    let Some(Item{node: Node::Atom(head), ..}) = children.first() else { return None };
+    match head.as_str() {
+        "fn" => children.iter()
+            .position(|i| matches!(i.node, Node::Group{open: langchars::BRACKET_OPEN, ..}))
+            .map(|p| p + 1),
+        "lambda" | "let" | "if" => Some(2),
+        _ => None,
+    }
+    .filter(|len| *len <= children.len())
+    .filter(|len| children[..*len].iter().all(|i| flat(&i.node).is_some() && !forced_break(&i.node)))
+}
+
+struct Printer<'o>{
+    options: &'o FormatOptions,
+    out: String,
+    column: usize,
+    after_comment: bool,
+}
+
+impl<'o> Printer<'o>{
+    fn write(&mut self, s: &str) {
+        self.out.push_str(s);
+        self.column += s.chars().count();
+    }
+
+    fn newline(&mut self, indent: usize, blank: bool) {
+        if blank {
+            self.out.push(langchars::NEW_LINE);
+        }
+        self.out.push(langchars::NEW_LINE);
+        self.out.push_str(&" ".repeat(indent));
+        self.column = indent;
+        self.after_comment = false;
+    }
+
+    //place an item right after the previous one, unless a comment forces a line break
+    fn inline(&mut self, item: &Item, break_indent: usize, separator: &str) {
+        match &item.node {
+            Node::Comment{trailing: true, ..} if !self.after_comment => {
+                self.write(" ");
+            },
+            Node::Comment{..} => self.newline(break_indent, false),
+            _ if self.after_comment => self.newline(break_indent, false),
+            _ => self.write(separator),
+        }
+        self.node(&item.node);
+    }
+
+    fn on_new_line(&mut self, item: &Item, indent: usize) {
+        match &item.node {
+            Node::Comment{trailing: true, ..} if !self.after_comment => self.write(" "),
+            _ => self.newline(indent, item.blank_before),
+        }
+        self.node(&item.node);
+    }
+
+    //plain atoms get packed onto as few lines as possible, anything else gets its own line
+    fn fill(&mut self, items: &[Item], indent: usize) {
+        let all_atoms = items.iter().all(|i| matches!(i.node, Node::Atom(_)));
+        for item in items {
+            match &item.node {
+                Node::Atom(a) if all_atoms && !item.blank_before && !self.after_comment
+                    && self.column + a.chars().count() + 2 <= self.options.width => {
+                    self.write(" ");
+                    self.write(a);
+                }
+                _ => self.on_new_line(item, indent),
+            }
+        }
+    }
+
+    fn node(&mut self, node: &Node) {
+        match node {
+            Node::Atom(a) => self.write(a),
+            Node::Comment{text, ..} => {
+                self.write(text);
+                self.after_comment = true;
+            }
+            Node::Group{open, close, children} => {
+                let fits = flat(node)
+                    .filter(|f| !forced_break(node) && self.column + f.chars().count() <= self.options.width);
+                match fits {
+                    Some(f) => self.write(&f),
+                    None => self.broken(*open, *close, children),
+                }
+            }
+        }
+    }
+
+    fn broken(&mut self, open: char, close: char, children: &[Item]) {
+        let start = self.column;
+        let body_indent = start + self.options.indent;
+        self.write(&open.to_string());
+        if open == langchars::BRACKET_OPEN {
+            children.iter().for_each(|i| self.on_new_line(i, body_indent));
+            self.newline(start, false);
+        } else if let Some(header) = header_len(children) {
+            children[..header].iter().enumerate()
+                .for_each(|(pos, i)| self.inline(i, body_indent, if pos == 0 {""} else {" "}));
+            children[header..].iter().for_each(|i| self.on_new_line(i, body_indent));
+        } else if let Some((first, rest)) = children.split_first() {
+            self.inline(first, start + 1, "");
+            let head_is_atom = matches!(first.node, Node::Atom(_));
+            //keep arguments lined up with the first one, if there is room for it
+            let hanging = head_is_atom
+                && !self.after_comment
+                && matches!(rest.first(), Some(i) if !matches!(i.node, Node::Comment{..}))
+                && self.column < self.options.width / 2;
+            if hanging {
+                let align = self.column + 1;
+                self.inline(&rest[0], body_indent, " ");
+                self.fill(&rest[1..], align);
+            } else if let Some((second, others)) = rest.split_first() {
+                let indent = if head_is_atom {body_indent} else {start + 1};
+                self.on_new_line(second, indent);
+                self.fill(others, indent);
+            }
+        }
+        if self.after_comment {
+            self.newline(start, false);
+        }
+        self.write(&close.to_string());
+    }
+}
+
+pub fn format_source(source: &str, options: &FormatOptions) -> Result<String, ParserError> {
+    let mut reader = Reader{
+        source: source.chars().collect(),
+        tokens: Lexer::from_text(source).with_comments().into_iter(),
+        last_line: 0,
+    };
+    let items = reader.read_items(None)?;
+    let mut printer = Printer{options, out: String::new(), column: 0, after_comment: false};
+    for (pos, item) in items.iter().enumerate() {
+        if pos == 0 {
+            printer.node(&item.node);
+        } else {
+            printer.on_new_line(item, 0);
+        }
+    }
+    if !printer.out.is_empty() {
+        printer.out.push(langchars::NEW_LINE);
+    }
+    Ok(printer.out)
+}
@@ -0,0 +1,69 @@
+use crate::lexer::{Lexer, TokenValue};
+
+//ansi colouring of source text, driven by the lexer so it agrees with the parser on what is a string or a comment
+
+pub const RESET: &str = "\x1b[0m";
+const NUMBER: &str = "\x1b[36m";
+const STRING: &str = "\x1b[32m";
+const COMMENT: &str = "\x1b[90m";
+const KEYWORD: &str = "\x1b[35m";
+const BUILTIN: &str = "\x1b[1;34m";
+const ERROR: &str = "\x1b[1;31m";
+//brackets take their colour from their depth, so the two ends of a pair always match
+const BRACKETS: [&str; 3] = ["\x1b[33m", "\x1b[35m", "\x1b[36m"];
+
+struct Span{
+    start: usize,
+    length: usize,
+    colour: &'static str,
+}
+
+fn spans(source: &str, is_builtin: &dyn Fn(&str) -> bool) -> Vec<Span> {
+    let mut spans = Vec::new();
+    //colours of the brackets still open
+    let mut open: Vec<&'static str> = Vec::new();
+    for token in Lexer::from_text(source).with_comments() {
+        let start = token.cursor.abs_position();
+        let reach = token.cursor.reach().unwrap_or(1);
+        let (length, colour) = match &token.value {
+            TokenValue::EOF => break,
+            TokenValue::ParenthesisOpen | TokenValue::BracketOpen => {
+                let colour = BRACKETS[open.len() % BRACKETS.len()];
+                open.push(colour);
+                (1, colour)
+            }
+            TokenValue::ParenthesisClose | TokenValue::BracketClose => (1, open.pop().unwrap_or(ERROR)),
+            TokenValue::Comment(text) => (text.chars().count(), COMMENT),
+            TokenValue::NumericToken(_) => (reach, NUMBER),
+            TokenValue::StringLiteral(_) => (reach, STRING),
+            TokenValue::UnterminatedString => (reach, ERROR),
+            TokenValue::Keyword(_) => (reach, KEYWORD),
+            TokenValue::Identifier(i) if is_builtin(i) => (reach, BUILTIN),
+            TokenValue::Identifier(_) => continue,
+        };
+        spans.push(Span{start, length, colour});
+    }
+    spans
+}
+
+pub fn highlight(source: &str, is_builtin: impl Fn(&str) -> bool) -> String {
+    let spans = spans(source, &is_builtin);
+    let mut out = String::with_capacity(source.len() * 2);
+    let mut spans = spans.iter().peekable();
+    let mut current: Option<&Span> = None;
+    for (pos, c) in source.chars().enumerate() {
+        if current.is_some_and(|s| pos == s.start + s.length) {
+            out.push_str(RESET);
+            current = None;
+        }
+        if let Some(span) = spans.next_if(|s| s.start == pos) {
+            out.push_str(span.colour);
+            current = Some(span);
+        }
+        out.push(c);
+    }
+    if current.is_some() {
+        out.push_str(RESET);
+    }
+    out
+}
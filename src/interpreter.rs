@@ -1,31 +1,263 @@
+use std::fmt::{Display, Formatter};
 use std::iter::Peekable;
+use std::path::Path;
 use std::rc::Rc;
 use std::slice::Iter;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+use std::{fmt, fs, io};
 use crate::ast::{PosExpression, SExpression};
+use crate::debugger::Debugger;
+use crate::profiler::Profiler;
+use crate::lexer::{escape_string, Lexer};
+use crate::parser::{parse, ParserError};
 use crate::value::{EvalContext, EvalResult, EvalValue, ReferenceValue};
 
 
+use crate::runtime::{Budget, Runtime, MAX_STACK_DEPTH};
 use crate::scope::{Scope, ScopeRef};
-use crate::stdlib::std_lib_functions;
+use crate::stdlib::{modules_functions, StdModule};
 use crate::value::builtin::{BuiltinFunction, BuiltInFunctionArg, BuiltInFunctionArgs};
-use crate::value::callable::{Callable, Function, Lambda, TailCall};
+use crate::value::callable::{Callable, Function, Lambda, Parameters, TailCall, LAMBDA_NAME};
+use crate::value::list::List;
 use crate::value::error::{ErrorContext, EvalError};
 
-fn env_scope() -> ScopeRef {
-    let scope = Scope::new();
+fn populate_builtins(scope: &ScopeRef, functions: Vec<BuiltinFunction>) {
     scope.insert("true".to_string(), EvalValue::True);
-    for bi in std_lib_functions().into_iter() {
-        //ReferenceValue::CallableValue(Callable::Internal(bi)).to_rc()
-        scope.insert(bi.name.to_string(), EvalValue::Reference(ReferenceValue::CallableValue(Callable::Internal(bi)).to_rc()))
+    for bi in functions.into_iter() {
+        scope.insert_builtin(bi);
     }
+}
+
+//only the selected modules are installed, a script can't reach builtins it wasn't given
+pub fn env_scope(modules: &[StdModule]) -> ScopeRef {
+    let scope = Scope::new();
+    populate_builtins(&scope, modules_functions(modules));
     scope
 }
 
+#[derive(Debug, Clone)]
+pub struct InterpreterOptions{
+    pub stack_limit: usize,
+    pub modules: Vec<StdModule>,
+    //applies to every eval_str, eval_file and call separately
+    pub budget: Budget,
+    //print every function and lambda call to stderr
+    pub trace: bool,
+}
+
+impl Default for InterpreterOptions{
+    fn default() -> Self {
+        InterpreterOptions{stack_limit: MAX_STACK_DEPTH, modules: StdModule::DEFAULT.to_vec(), budget: Budget::default(), trace: false}
+    }
+}
+
+#[derive(Debug)]
+pub enum InterpreterError{
+    Parser(ParserError),
+    Eval(ErrorContext),
+    Io(io::Error),
+}
+
+impl Display for InterpreterError{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            InterpreterError::Parser(e) => f.write_fmt(format_args!("Parser: {}", e)),
+            InterpreterError::Eval(e) => {
+                f.write_fmt(format_args!("Eval: {}", e.error()))?;
+                //innermost call first, like the debugger's backtrace
+                for (depth, name) in e.stack_trace().iter().flat_map(|t| t.trace.iter()).enumerate() {
+                    f.write_fmt(format_args!("\n  #{} {}", depth, name))?;
+                }
+                Ok(())
+            }
+            InterpreterError::Io(e) => f.write_fmt(format_args!("Io: {}", e)),
+        }
+    }
+}
+
+impl InterpreterError{
+    //the status the script asked to exit with
+    pub fn exit_code(&self) -> Option<i32> {
+        match self {
+            InterpreterError::Eval(e) => match e.error() {
+                EvalError::Exit(code) => Some(*code),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+}
+
+impl From<ParserError> for InterpreterError{
+    fn from(e: ParserError) -> Self {
+        InterpreterError::Parser(e)
+    }
+}
+
+impl From<ErrorContext> for InterpreterError{
+    fn from(e: ErrorContext) -> Self {
+        InterpreterError::Eval(e)
+    }
+}
+
+impl From<io::Error> for InterpreterError{
+    fn from(e: io::Error) -> Self {
+        InterpreterError::Io(e)
+    }
+}
+
+//entry point for hosting kisp, definitions persist between evaluations
+pub struct Interpreter{
+    //builtins live one level above the user's definitions
+    globals: ScopeRef,
+    scope: ScopeRef,
+}
+
+impl Default for Interpreter{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Interpreter{
+    pub fn new() -> Interpreter {
+        Self::with_options(InterpreterOptions::default())
+    }
+
+    pub fn with_options(options: InterpreterOptions) -> Interpreter {
+        let globals = Scope::with_runtime(Rc::new(Runtime::new(options.stack_limit, options.budget)));
+        populate_builtins(&globals, modules_functions(&options.modules));
+        globals.runtime.set_trace_all(options.trace);
+        let scope = globals.child(None, None);
+        Interpreter{globals, scope}
+    }
+
+    pub fn scope(&self) -> &ScopeRef {
+        &self.scope
+    }
+
+    //setting the flag aborts the running evaluation with EvalError::Interrupted
+    pub fn interrupt_handle(&self) -> Arc<AtomicBool> {
+        self.globals.runtime.interrupt_handle()
+    }
+
+    //the hooks are called for every evaluation until they are removed with None
+    pub fn set_debugger(&self, debugger: Option<Rc<dyn Debugger>>) {
+        self.globals.runtime.set_debugger(debugger);
+    }
+
+    //function and lambda calls are recorded until it is removed with None
+    pub fn set_profiler(&self, profiler: Option<Rc<Profiler>>) {
+        self.globals.runtime.set_profiler(profiler);
+    }
+
+    pub fn eval_str(&self, source: &str) -> Result<EvalValue, InterpreterError> {
+        let ast = parse(&mut Lexer::from_text(source).into_iter())?;
+        self.scope.runtime.start();
+        let (result, _) = eval(&ast, Some(self.scope.clone()));
+        Ok(result?.0)
+    }
+
+    pub fn eval_file(&self, path: impl AsRef<Path>) -> Result<EvalValue, InterpreterError> {
+        let source = fs::read_to_string(path)?;
+        self.eval_str(&source)
+    }
+
+    pub fn define(&self, name: &str, value: EvalValue) {
+        self.scope.insert(name.to_string(), value);
+    }
+
+    //builtins registered by the host are global, they survive a reset
+    pub fn register(&self, builtin: BuiltinFunction) {
+        self.globals.insert_builtin(builtin);
+    }
+
+    pub fn register_fn(&self, name: impl Into<String>, callback: impl Fn(&ScopeRef, EvalContext, BuiltInFunctionArgs) -> EvalResult + 'static) {
+        self.register(BuiltinFunction::new(name, callback));
+    }
+
+    pub fn get(&self, name: &str) -> Option<EvalValue> {
+        self.scope.lookup(&name.to_string())
+    }
+
+    pub fn call(&self, name: &str, args: Vec<EvalValue>) -> Result<EvalValue, InterpreterError> {
+        let value = self.get(name)
+            .ok_or_else(|| EvalError::UnknownSymbol(name.to_string()).trace(&self.scope))?;
+        let EvalValue::Reference(r) = &value else {
+            return Err(EvalError::CallingNonCallable.trace(&self.scope).into());
+        };
+        let ReferenceValue::CallableValue(callable) = r.as_ref() else {
+            return Err(EvalError::CallingNonCallable.trace(&self.scope).into());
+        };
+        self.scope.runtime.start();
+        Ok(eval_call_with_values(EvalContext::none(), &self.scope, callable, args, Some(r.clone()))?.0)
+    }
+
+    //drops user definitions, builtins stay
+    pub fn reset(&mut self) {
+        self.scope = self.globals.child(None, None);
+    }
+
+    //user definitions as kisp source: data, then function definitions, then the names that alias them
+    pub fn session_source(&self) -> String {
+        let bindings = self.scope.local_bindings();
+        //the binding itself, not just one with the same name, must be saved for an alias to load
+        let saved = |target: &str, r: &Rc<ReferenceValue>| bindings.iter().any(|(name, value)| name == target
+            && matches!(value, EvalValue::Reference(v) if Rc::ptr_eq(v, r)));
+        let mut data = Vec::new();
+        let mut functions = Vec::new();
+        let mut aliases = Vec::new();
+        for (name, value) in &bindings {
+            let EvalValue::Reference(r) = value else {
+                data.push(format!("(let {} {})", name, value.repr()));
+                continue;
+            };
+            match r.as_ref() {
+                //closures over a call's locals would lose them
+                ReferenceValue::CallableValue(Callable::Function(Function{in_scope, ..}) | Callable::Lambda(Lambda{in_scope, ..}))
+                    if !Rc::ptr_eq(in_scope, &self.scope) =>
+                    data.push(format!("; {} skipped, it was defined inside another function", name)),
+                ReferenceValue::CallableValue(Callable::Function(f)) if f.name == *name => {
+                    let doc = f.doc.as_deref().map(|d| format!("{} ", escape_string(d))).unwrap_or_default();
+                    functions.push(format!("(fn {} {} {}{})", name, f.arguments, doc, f.body.exp))
+                }
+                ReferenceValue::CallableValue(Callable::Function(f)) if saved(&f.name, r) =>
+                    aliases.push(format!("(let {} {})", name, f.name)),
+                ReferenceValue::CallableValue(Callable::Function(f)) =>
+                    data.push(format!("; {} skipped, {} was redefined", name, f.name)),
+                ReferenceValue::CallableValue(Callable::Lambda(l)) =>
+                    functions.push(format!("(let {} (lambda {} {}))", name, l.arguments, l.body.exp)),
+                ReferenceValue::CallableValue(Callable::Internal(b)) =>
+                    aliases.push(format!("(let {} {})", name, b.name)),
+                _ if value.is_data() => data.push(format!("(let {} {})", name, value.repr())),
+                _ => data.push(format!("; {} skipped, a {} can't be saved", name, value.type_name())),
+            }
+        }
+        data.into_iter().chain(functions).chain(aliases).map(|line| line + "\n").collect()
+    }
+
+    pub fn save_session(&self, path: impl AsRef<Path>) -> Result<(), InterpreterError> {
+        Ok(fs::write(path, self.session_source())?)
+    }
+
+    //replaces the current definitions with the ones in the file
+    pub fn load_session(&mut self, path: impl AsRef<Path>) -> Result<(), InterpreterError> {
+        let source = fs::read_to_string(path)?;
+        let previous = std::mem::replace(&mut self.scope, self.globals.child(None, None));
+        if let Err(e) = self.eval_str(&source) {
+            self.scope = previous;
+            return Err(e);
+        }
+        Ok(())
+    }
+}
+
 pub fn eval(ast: &'_ PosExpression, provided_scope: Option<ScopeRef>) -> (EvalResult, ScopeRef) {
     let env = if let Some(provided) = provided_scope{
         provided
     }else{
-        env_scope()
+        env_scope(&StdModule::DEFAULT)
     };
     let res = match &ast.exp {
         //don't create a new scope!
@@ -36,24 +268,86 @@ pub fn eval(ast: &'_ PosExpression, provided_scope: Option<ScopeRef>) -> (EvalRe
 }
 
 pub(crate) fn eval_expression(ctx: EvalContext, scope: &ScopeRef, expression: &'_ PosExpression) -> EvalResult {
+    scope.runtime.step().map_err(|e| e.trace(scope))?;
+    let Some(debugger) = scope.runtime.debugger() else {
+        return eval_expression_value(ctx, scope, expression);
+    };
+    debugger.before(scope, expression).map_err(|e| e.trace(scope))?;
+    let result = eval_expression_value(ctx, scope, expression);
+    debugger.after(scope, expression, &result);
+    result
+}
+
+fn eval_expression_value(ctx: EvalContext, scope: &ScopeRef, expression: &'_ PosExpression) -> EvalResult {
     match &expression.exp {
         SExpression::Symbol(i) => scope.lookup(i).map_or(
             Err(EvalError::UnknownSymbol(i.clone()).trace(scope)),
             |v| Ok((v.clone(), EvalContext::none()))
         ),
         SExpression::Number(i) => Ok((EvalValue::Numeric(i.clone()), EvalContext::none())),
+        SExpression::Keyword(k) => Ok((EvalValue::Reference(ReferenceValue::Keyword(k.clone()).to_rc()), EvalContext::none())),
+        SExpression::String(s) => Ok((EvalValue::Reference(ReferenceValue::String(s.clone()).to_rc()), EvalContext::none())),
         SExpression::List(expressions) => eval_list(ctx, scope, expressions),
         SExpression::Block(expressions) => eval_block(ctx, scope, expressions, false),
     }
 }
 
-fn populate_scope_with_args(scope: &ScopeRef, values: Vec<EvalValue>, arg_names: &Vec<String>) -> () {
-    arg_names.iter()
-        .zip(values)
-        .for_each(
-            |(ident, val)|
-            scope.insert(ident.clone(), val)
-        );
+//positional arguments and the `:key value` pairs taken out of them
+type SplitArguments = (Vec<EvalValue>, Vec<(String, EvalValue)>);
+
+//pulls `:key value` pairs for declared keyword parameters out of the argument list
+pub(crate) fn split_keywords(scope: &ScopeRef, values: Vec<EvalValue>, declared: &[impl AsRef<str>]) -> Result<SplitArguments, ErrorContext> {
+    let mut positional = Vec::with_capacity(values.len());
+    let mut named = Vec::new();
+    let mut iter = values.into_iter();
+    while let Some(value) = iter.next() {
+        match value.keyword().filter(|k| declared.iter().any(|d| d.as_ref() == *k)) {
+            Some(k) => {
+                let k = k.to_string();
+                let named_value = iter.next().ok_or_else(|| EvalError::MissingArgument.trace(scope))?;
+                named.push((k, named_value));
+            }
+            None => positional.push(value),
+        }
+    }
+    Ok((positional, named))
+}
+
+fn bind_arguments(scope: &ScopeRef, name: &str, values: Vec<EvalValue>, params: &Parameters) -> Result<(), ErrorContext> {
+    scope.set_vararg(values.clone());
+    let declared: Vec<&str> = params.keyword.iter().map(|(k, _)| k.as_str()).collect();
+    let (values, mut named) = split_keywords(scope, values, &declared)?;
+    if !params.accepts(values.len()) {
+        return Err(EvalError::ArityMismatch{
+            function: name.to_string(),
+            min: params.min(),
+            max: params.max(),
+            actual: values.len(),
+        }.trace(scope));
+    }
+    let mut values = values.into_iter();
+    for ident in params.required.iter() {
+        scope.insert(ident.clone(), values.next().unwrap());
+    }
+    for (ident, default) in params.optional.iter() {
+        let value = match values.next() {
+            Some(v) => v,
+            None => eval_expression(EvalContext::none(), scope, default)?.0,
+        };
+        scope.insert(ident.clone(), value);
+    }
+    for (ident, default) in params.keyword.iter() {
+        //last one wins if a keyword is passed twice
+        let value = match named.iter().rposition(|(k, _)| k == ident) {
+            Some(pos) => named.swap_remove(pos).1,
+            None => eval_expression(EvalContext::none(), scope, default)?.0,
+        };
+        scope.insert(ident.clone(), value);
+    }
+    if let Some(ident) = &params.rest {
+        scope.insert(ident.clone(), EvalValue::Reference(ReferenceValue::List(List::from(values.collect())).to_rc()));
+    }
+    Ok(())
 }
 
 fn is_tail_call(ctx: &EvalContext, origin: &Option<Rc<ReferenceValue>>, inside: &Option<Rc<ReferenceValue>>) -> bool {
@@ -64,40 +358,54 @@ fn is_tail_call(ctx: &EvalContext, origin: &Option<Rc<ReferenceValue>>, inside:
 }
 
 
-pub fn wrap_tail_call(ctx: EvalContext, scope: &ScopeRef, passed_in: Vec<EvalValue>, arg_names: &Vec<String>, expression: &PosExpression, origin: Option<Rc<ReferenceValue>>) -> EvalResult {
+pub fn wrap_tail_call(ctx: EvalContext, scope: &ScopeRef, name: &str, passed_in: Vec<EvalValue>, params: &Parameters, expression: &PosExpression, origin: Option<Rc<ReferenceValue>>) -> EvalResult {
     let tc_detected = is_tail_call(&ctx, &scope.origin,&origin);
     if tc_detected{
         let tc: TailCall = TailCall{ function: origin.unwrap().clone(), args: passed_in };
         Ok((EvalValue::Reference(ReferenceValue::TailCallValue(tc).to_rc()), ctx))
     }else {
-        eval_with_args(ctx, scope, passed_in, arg_names, expression, origin)
+        eval_with_args(ctx, scope, name, passed_in, params, expression, origin)
     }
 }
 
-pub(crate) fn eval_with_args_flat(given_ctx: EvalContext, scope: &ScopeRef, passed_in: Vec<EvalValue>, arg_names: &Vec<String>, expression: &PosExpression, _origin: Option<Rc<ReferenceValue>>) -> EvalResult {
-    populate_scope_with_args(&scope, passed_in, arg_names);
+pub(crate) fn eval_with_args_flat(given_ctx: EvalContext, scope: &ScopeRef, name: &str, passed_in: Vec<EvalValue>, params: &Parameters, expression: &PosExpression, _origin: Option<Rc<ReferenceValue>>) -> EvalResult {
+    bind_arguments(scope, name, passed_in, params)?;
+    let debugger = scope.runtime.debugger();
+    if let Some(debugger) = &debugger {
+        debugger.enter(scope, name);
+    }
+    let result = eval_body(scope, name, params, expression);
+    if let Some(debugger) = &debugger {
+        debugger.leave(scope, name, &result);
+    }
+    let (res, res_ctx) = result?;
+    Ok((res, EvalContext{possible_tail: given_ctx.possible_tail && res_ctx.possible_tail}))
+}
+
+//the body with its arguments bound, tail calls loop here instead of growing the stack
+fn eval_body(scope: &ScopeRef, name: &str, params: &Parameters, expression: &PosExpression) -> EvalResult {
     let (mut res, mut res_ctx) = eval_expression(
         EvalContext{possible_tail: true}, //there we go, tail recursion
-        &scope,
+        scope,
         expression
     )?;
     while let EvalValue::Reference(r)= &res {
         match r.as_ref(){
             ReferenceValue::TailCallValue(tc) => {
                 scope.clear();
-                populate_scope_with_args(&scope, tc.args.clone(), arg_names);
+                bind_arguments(scope, name, tc.args.clone(), params)?;
                 (res, res_ctx) = eval_expression(EvalContext{possible_tail: true}, scope, expression)?;
             },
             _=> break
         }
 
     }
-    Ok((res, EvalContext{possible_tail: given_ctx.possible_tail && res_ctx.possible_tail}))
+    Ok((res, res_ctx))
 }
 
-pub(crate) fn eval_with_args(ctx: EvalContext, scope: &ScopeRef, passed_in: Vec<EvalValue>, arg_names: &Vec<String>, expression: &PosExpression, origin: Option<Rc<ReferenceValue>>) -> EvalResult {
+pub(crate) fn eval_with_args(ctx: EvalContext, scope: &ScopeRef, name: &str, passed_in: Vec<EvalValue>, params: &Parameters, expression: &PosExpression, origin: Option<Rc<ReferenceValue>>) -> EvalResult {
     let func_scope = scope.enter(origin.clone())?;
-    eval_with_args_flat(ctx, &func_scope, passed_in, arg_names, expression, origin)
+    eval_with_args_flat(ctx, &func_scope, name, passed_in, params, expression, origin)
 }
 
 
@@ -108,15 +416,47 @@ fn eval_all(_ctx: EvalContext, scope: &ScopeRef, exps: & [PosExpression]) -> Res
 }
 
 pub(crate) fn eval_call_with_values(ctx: EvalContext, scope: &ScopeRef, callable: &Callable, args: Vec<EvalValue>, origin: Option<Rc<ReferenceValue>>) -> EvalResult {
+    let profiler = match callable {
+        Callable::Internal(_) => None,
+        _ => scope.runtime.profiler(),
+    };
+    let Some(profiler) = profiler else {
+        return eval_call_traced(ctx, scope, callable, args, origin);
+    };
+    profiler.enter(callable.label());
+    let result = eval_call_traced(ctx, scope, callable, args, origin);
+    profiler.leave();
+    result
+}
+
+fn eval_call_traced(ctx: EvalContext, scope: &ScopeRef, callable: &Callable, args: Vec<EvalValue>, origin: Option<Rc<ReferenceValue>>) -> EvalResult {
+    if !scope.runtime.is_traced(callable) {
+        return eval_call_untraced(ctx, scope, callable, args, origin);
+    }
+    let indent = "  ".repeat(scope.depth.saturating_sub(1));
+    let label = callable.label();
+    let shown: Vec<String> = args.iter().map(|a| a.repr()).collect();
+    eprintln!("{}({})", indent, std::iter::once(label.clone()).chain(shown).collect::<Vec<String>>().join(" "));
+    let result = eval_call_untraced(ctx, scope, callable, args, origin);
+    match &result {
+        //a tail call comes back as a marker, the call that started the loop prints the outcome
+        Ok((EvalValue::Reference(r), _)) if matches!(r.as_ref(), ReferenceValue::TailCallValue(_)) => {}
+        Ok((value, _)) => eprintln!("{}{} => {}", indent, label, value.repr()),
+        Err(e) => eprintln!("{}{} failed: {}", indent, label, e.error()),
+    }
+    result
+}
+
+fn eval_call_untraced(ctx: EvalContext, scope: &ScopeRef, callable: &Callable, args: Vec<EvalValue>, origin: Option<Rc<ReferenceValue>>) -> EvalResult {
     match callable {
-        Callable::Internal(BuiltinFunction{callback,..}) => callback(
+        Callable::Internal(BuiltinFunction{callback, keywords, ..}) => callback(
             scope,
             ctx,
-            BuiltInFunctionArgs::from(args),
+            BuiltInFunctionArgs::with_keywords(scope, args, keywords)?,
         ),
         Callable::Function(func) =>
-            wrap_tail_call(ctx, scope, args, &func.arguments, &func.body, origin),
-        Callable::Lambda(lam) => eval_with_args(EvalContext::none(), scope, args, &lam.arguments, &lam.body, None),
+            wrap_tail_call(ctx, scope, &func.name, args, &func.arguments, &func.body, origin),
+        Callable::Lambda(lam) => eval_with_args(EvalContext::none(), scope, LAMBDA_NAME, args, &lam.arguments, &lam.body, None),
     }
 }
 
@@ -125,7 +465,7 @@ pub(crate) fn eval_callable(ctx: EvalContext, scope: &ScopeRef, callable: &Calla
     match callable {
         Callable::Internal(bi) => {
             let exp_args: Vec<EvalValue> = args.iter().map(|exp| EvalValue::Reference(ReferenceValue::Expression(exp.clone()).to_rc())).collect();
-            (bi.callback)(scope, ctx, BuiltInFunctionArgs::from(exp_args))
+            (bi.callback)(scope, ctx, BuiltInFunctionArgs::with_keywords(scope, exp_args, &bi.keywords)?)
         },
         Callable::Function(Function{arguments: _, body: _,..}) =>
             eval_call_with_values(ctx, scope, callable, eval_all(EvalContext::none(), scope, args)?, origin),
@@ -0,0 +1,362 @@
+use std::fmt;
+use std::fmt::{Display, Formatter, Write};
+use std::iter::Peekable;
+use std::str::Chars;
+
+//small self contained json reader/writer, enough for the language server and data exchange
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum JsonValue{
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<JsonValue>),
+    //keeps insertion order, objects are small anyway
+    Object(Vec<(String, JsonValue)>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct JsonError{
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl Display for JsonError{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_fmt(format_args!("{} at line {} column {}", self.message, self.line, self.column))
+    }
+}
+
+impl JsonValue{
+    pub fn get(&self, key: &str) -> Option<&JsonValue> {
+        match self {
+            JsonValue::Object(entries) => entries.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            JsonValue::String(s) => Some(s.as_str()),
+            _ => None,
+        }
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            JsonValue::Number(n) => Some(*n),
+            _ => None,
+        }
+    }
+
+    pub fn as_usize(&self) -> Option<usize> {
+        self.as_f64().filter(|n| *n >= 0.0 && n.fract() == 0.0).map(|n| n as usize)
+    }
+
+    pub fn object(entries: Vec<(&str, JsonValue)>) -> JsonValue {
+        JsonValue::Object(entries.into_iter().map(|(k, v)| (k.to_string(), v)).collect())
+    }
+
+    pub fn string(s: impl Into<String>) -> JsonValue {
+        JsonValue::String(s.into())
+    }
+}
+
+//arrays and objects nested deeper than this are rejected instead of overflowing the stack
+const MAX_DEPTH: usize = 512;
+
+struct Reader<'a>{
+    chars: Peekable<Chars<'a>>,
+    line: usize,
+    column: usize,
+    depth: usize,
+}
+
+impl<'a> Reader<'a>{
+    fn error(&self, message: impl Into<String>) -> JsonError {
+        JsonError{line: self.line, column: self.column, message: message.into()}
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.chars.next()?;
+        if c == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+        Some(c)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.chars.peek(), Some(' ' | '\t' | '\n' | '\r')) {
+            self.next();
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), JsonError> {
+        self.skip_whitespace();
+        match self.chars.peek().copied() {
+            Some(c) if c == expected => { self.next(); Ok(()) }
+            Some(c) => Err(self.error(format!("expected '{}', found '{}'", expected, c))),
+            None => Err(self.error(format!("expected '{}', found end of input", expected))),
+        }
+    }
+
+    fn literal(&mut self, word: &str, value: JsonValue) -> Result<JsonValue, JsonError> {
+        for expected in word.chars() {
+            if self.chars.peek() != Some(&expected) {
+                return Err(self.error(format!("invalid literal, expected {}", word)));
+            }
+            self.next();
+        }
+        Ok(value)
+    }
+
+    fn value(&mut self) -> Result<JsonValue, JsonError> {
+        self.skip_whitespace();
+        match self.chars.peek().copied() {
+            None => Err(self.error("unexpected end of input")),
+            Some('n') => self.literal("null", JsonValue::Null),
+            Some('t') => self.literal("true", JsonValue::Bool(true)),
+            Some('f') => self.literal("false", JsonValue::Bool(false)),
+            Some('"') => self.string().map(JsonValue::String),
+            Some('[') => self.nested(Reader::array),
+            Some('{') => self.nested(Reader::object),
+            Some(c) if c == '-' || c.is_ascii_digit() => self.number(),
+            Some(c) => Err(self.error(format!("unexpected character '{}'", c))),
+        }
+    }
+
+    fn nested(&mut self, read: fn(&mut Reader<'a>) -> Result<JsonValue, JsonError>) -> Result<JsonValue, JsonError> {
+        if self.depth >= MAX_DEPTH {
+            return Err(self.error(format!("nested deeper than {} levels", MAX_DEPTH)));
+        }
+        self.depth += 1;
+        let value = read(self);
+        self.depth -= 1;
+        value
+    }
+
+    fn number(&mut self) -> Result<JsonValue, JsonError> {
+        let (line, column) = (self.line, self.column);
+        let mut text = String::new();
+        while let Some(c) = self.chars.peek().copied() {
+            if !(c.is_ascii_digit() || matches!(c, '-' | '+' | '.' | 'e' | 'E')) {
+                break;
+            }
+            text.push(c);
+            self.next();
+        }
+        match text.parse::<f64>() {
+            //json has no infinity, 1e999 would come back out as null
+            Ok(number) if number.is_finite() => Ok(JsonValue::Number(number)),
+            Ok(_) => Err(JsonError{line, column, message: format!("number out of range {}", text)}),
+            Err(_) => Err(JsonError{line, column, message: format!("invalid number {}", text)}),
+        }
+    }
+
+    fn hex_escape(&mut self) -> Result<u32, JsonError> {
+        let mut code = 0;
+        for _ in 0..4 {
+            let digit = self.next()
+                .and_then(|c| c.to_digit(16))
+                .ok_or_else(|| self.error("invalid unicode escape"))?;
+            code = code * 16 + digit;
+        }
+        Ok(code)
+    }
+
+    fn string(&mut self) -> Result<String, JsonError> {
+        self.expect('"')?;
+        let mut out = String::new();
+        loop {
+            match self.next() {
+                None => return Err(self.error("unterminated string")),
+                Some('"') => return Ok(out),
+                Some('\\') => {
+                    let escaped = match self.next() {
+                        Some('"') => '"',
+                        Some('\\') => '\\',
+                        Some('/') => '/',
+                        Some('b') => '\u{8}',
+                        Some('f') => '\u{c}',
+                        Some('n') => '\n',
+                        Some('r') => '\r',
+                        Some('t') => '\t',
+                        Some('u') => {
+                            let high = self.hex_escape()?;
+                            let code = if (0xD800..0xDC00).contains(&high) {
+                                //surrogate pair
+                                self.literal("\\u", JsonValue::Null)?;
+                                let low = self.hex_escape()?;
+                                if !(0xDC00..0xE000).contains(&low) {
+                                    return Err(self.error("invalid surrogate pair"));
+                                }
+                                0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00)
+                            } else if (0xDC00..0xE000).contains(&high) {
+                                return Err(self.error("invalid surrogate pair"));
+                            } else {
+                                high
+                            };
+                            char::from_u32(code).ok_or_else(|| self.error("invalid unicode escape"))?
+                        }
+                        _ => return Err(self.error("invalid escape sequence")),
+                    };
+                    out.push(escaped);
+                }
+                Some(c) => out.push(c),
+            }
+        }
+    }
+
+    fn array(&mut self) -> Result<JsonValue, JsonError> {
+        self.expect('[')?;
+        let mut values = Vec::new();
+        self.skip_whitespace();
+        if self.chars.peek() == Some(&']') {
+            self.next();
+            return Ok(JsonValue::Array(values));
+        }
+        loop {
+            values.push(self.value()?);
+            self.skip_whitespace();
+            match self.next() {
+                Some(',') => continue,
+                Some(']') => return Ok(JsonValue::Array(values)),
+                _ => return Err(self.error("expected ',' or ']'")),
+            }
+        }
+    }
+
+    fn object(&mut self) -> Result<JsonValue, JsonError> {
+        self.expect('{')?;
+        let mut entries = Vec::new();
+        self.skip_whitespace();
+        if self.chars.peek() == Some(&'}') {
+            self.next();
+            return Ok(JsonValue::Object(entries));
+        }
+        loop {
+            self.skip_whitespace();
+            let key = self.string()?;
+            self.expect(':')?;
+            entries.push((key, self.value()?));
+            self.skip_whitespace();
+            match self.next() {
+                Some(',') => continue,
+                Some('}') => return Ok(JsonValue::Object(entries)),
+                _ => return Err(self.error("expected ',' or '}'")),
+            }
+        }
+    }
+}
+
+pub fn parse(input: &str) -> Result<JsonValue, JsonError> {
+    let mut reader = Reader{chars: input.chars().peekable(), line: 1, column: 1, depth: 0};
+    let value = reader.value()?;
+    reader.skip_whitespace();
+    match reader.chars.peek().copied() {
+        None => Ok(value),
+        Some(c) => Err(reader.error(format!("trailing character '{}'", c))),
+    }
+}
+
+fn write_string(f: &mut impl Write, s: &str) -> fmt::Result {
+    f.write_char('"')?;
+    for c in s.chars() {
+        match c {
+            '"' => f.write_str("\\\"")?,
+            '\\' => f.write_str("\\\\")?,
+            '\n' => f.write_str("\\n")?,
+            '\r' => f.write_str("\\r")?,
+            '\t' => f.write_str("\\t")?,
+            c if (c as u32) < 0x20 => f.write_fmt(format_args!("\\u{:04x}", c as u32))?,
+            c => f.write_char(c)?,
+        }
+    }
+    f.write_char('"')
+}
+
+fn write_number(f: &mut impl Write, n: f64) -> fmt::Result {
+    if !n.is_finite() {
+        //json has no representation for these
+        f.write_str("null")
+    } else if n.fract() == 0.0 && n.abs() < 1e15 {
+        f.write_fmt(format_args!("{}", n as i64))
+    } else {
+        f.write_fmt(format_args!("{}", n))
+    }
+}
+
+impl JsonValue{
+    //multi line output, nested values indented by `indent` spaces per level
+    pub fn pretty(&self, indent: usize) -> String {
+        let mut out = String::new();
+        self.write_pretty(&mut out, indent, 0).unwrap();
+        out
+    }
+
+    fn write_pretty(&self, f: &mut String, indent: usize, depth: usize) -> fmt::Result {
+        let newline = |f: &mut String, depth: usize| {
+            f.push('\n');
+            f.push_str(&" ".repeat(indent * depth));
+        };
+        match self {
+            JsonValue::Array(values) if !values.is_empty() => {
+                f.write_char('[')?;
+                for (pos, v) in values.iter().enumerate() {
+                    if pos > 0 { f.write_char(',')?; }
+                    newline(f, depth + 1);
+                    v.write_pretty(f, indent, depth + 1)?;
+                }
+                newline(f, depth);
+                f.write_char(']')
+            }
+            JsonValue::Object(entries) if !entries.is_empty() => {
+                f.write_char('{')?;
+                for (pos, (k, v)) in entries.iter().enumerate() {
+                    if pos > 0 { f.write_char(',')?; }
+                    newline(f, depth + 1);
+                    write_string(f, k)?;
+                    f.write_str(": ")?;
+                    v.write_pretty(f, indent, depth + 1)?;
+                }
+                newline(f, depth);
+                f.write_char('}')
+            }
+            v => f.write_fmt(format_args!("{}", v)),
+        }
+    }
+}
+
+impl Display for JsonValue{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            JsonValue::Null => f.write_str("null"),
+            JsonValue::Bool(b) => f.write_fmt(format_args!("{}", b)),
+            JsonValue::Number(n) => write_number(f, *n),
+            JsonValue::String(s) => write_string(f, s),
+            JsonValue::Array(values) => {
+                f.write_char('[')?;
+                for (pos, v) in values.iter().enumerate() {
+                    if pos > 0 { f.write_char(',')?; }
+                    Display::fmt(v, f)?;
+                }
+                f.write_char(']')
+            }
+            JsonValue::Object(entries) => {
+                f.write_char('{')?;
+                for (pos, (k, v)) in entries.iter().enumerate() {
+                    if pos > 0 { f.write_char(',')?; }
+                    write_string(f, k)?;
+                    f.write_char(':')?;
+                    Display::fmt(v, f)?;
+                }
+                f.write_char('}')
+            }
+        }
+    }
+}
@@ -3,6 +3,9 @@ use crate::value::numeric::Numeric;
 
 pub mod langchars {
     pub const COMMENT: char= ';';
+    pub const KEYWORD_PREFIX: char = ':';
+    pub const STRING_DELIMITER: char = '"';
+    pub const ESCAPE: char = '\\';
     pub const PARENTHESIS_OPEN: char = '(';
     pub const PARENTHESIS_CLOSE: char = ')';
     pub const BRACKET_OPEN: char = '[';
@@ -11,9 +14,42 @@ pub mod langchars {
     pub const SPACE: char = ' ';
     pub const TAB: char = '\t';
     pub const NEW_LINE: char = '\n';
+    pub const CARRIAGE_RETURN: char = '\r';
 
     //disallowed in identifiers
-    pub const NON_IDENTIFIER_CHARS: [char; 7] = [PARENTHESIS_OPEN, PARENTHESIS_CLOSE, BRACKET_OPEN, BRACKET_CLOSE, SPACE, TAB, NEW_LINE];
+    pub const NON_IDENTIFIER_CHARS: [char; 9] = [PARENTHESIS_OPEN, PARENTHESIS_CLOSE, BRACKET_OPEN, BRACKET_CLOSE, SPACE, TAB, NEW_LINE, CARRIAGE_RETURN, STRING_DELIMITER];
+}
+
+//inverse of what the lexer does to a string literal, quotes included
+pub fn escape_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push(langchars::STRING_DELIMITER);
+    for c in s.chars() {
+        match c {
+            langchars::STRING_DELIMITER | langchars::ESCAPE => { out.push(langchars::ESCAPE); out.push(c); }
+            '\n' => out.push_str("\\n"),
+            '\t' => out.push_str("\\t"),
+            '\r' => out.push_str("\\r"),
+            c => out.push(c),
+        }
+    }
+    out.push(langchars::STRING_DELIMITER);
+    out
+}
+
+//parentheses and brackets still open at the end of the source, an unfinished string counts as one more
+pub fn open_depth(source: &str) -> usize {
+    let mut depth: usize = 0;
+    for token in Lexer::from_text(source) {
+        match token.value {
+            TokenValue::ParenthesisOpen | TokenValue::BracketOpen => depth += 1,
+            TokenValue::ParenthesisClose | TokenValue::BracketClose => depth = depth.saturating_sub(1),
+            TokenValue::UnterminatedString => return depth + 1,
+            TokenValue::EOF => break,
+            _ => {}
+        }
+    }
+    depth
 }
 
 #[derive(Clone, Debug)]
@@ -38,6 +74,23 @@ impl Cursor{
         clone
     }
 
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    pub fn column(&self) -> usize {
+        self.column
+    }
+
+    pub fn abs_position(&self) -> usize {
+        self.abs_position
+    }
+
+    //length of the token in chars, only known for identifiers, numbers and strings
+    pub fn reach(&self) -> Option<usize> {
+        self._reach
+    }
+
     fn next_line(&self) -> Cursor{
         let mut clone = self.clone();
         clone.line+=1;
@@ -49,6 +102,8 @@ impl Cursor{
 
 pub struct Lexer<'a>{
     txt_buffer: &'a str,
+    //comments are usually thrown away, the formatter needs them though
+    keep_comments: bool,
 }
 /*
 pub enum Keyword{
@@ -60,12 +115,16 @@ pub enum Keyword{
 pub enum TokenValue{
     Identifier(String),
     NumericToken(Numeric),
+    Keyword(String),
     //IntToken(i32),
-    //StringLiteral(String),
+    StringLiteral(String),
+    //missing the closing quote, the parser turns it into an error
+    UnterminatedString,
     ParenthesisOpen,
     ParenthesisClose,
     BracketOpen,
     BracketClose,
+    Comment(String),
     EOF,
 }
 
@@ -87,7 +146,11 @@ impl<'t> Lexer<'t>{
     }
 
     pub fn from_text(data: &str) -> Lexer {
-        Lexer{txt_buffer: data}
+        Lexer{txt_buffer: data, keep_comments: false}
+    }
+
+    pub fn with_comments(self) -> Lexer<'t> {
+        Lexer{keep_comments: true, ..self}
     }
 
     fn char_at(&self, i: usize) -> Option<char> {
@@ -104,6 +167,9 @@ impl<'t> Lexer<'t>{
     }
 
     fn possible_identifier_upgrade(input: &String) -> Option<TokenValue> {
+        if let Some(keyword) = input.strip_prefix(langchars::KEYWORD_PREFIX).filter(|k| !k.is_empty()) {
+            return Some(TokenValue::Keyword(keyword.to_string()));
+        }
         //TODO: this is AWFUL, refactor when BigInt is added
         input.parse::<i32>()
             .map(|v| Some(TokenValue::NumericToken(Numeric::Integer(v)))).unwrap_or_else(
@@ -124,6 +190,34 @@ impl<'t> Lexer<'t>{
         (TokenValue::Identifier(ident),cursor.next_columns(len))
     }
 
+    fn read_string(&self, start: &Cursor) -> (TokenValue, Cursor) {
+        let mut cursor = start.next_column();
+        let mut value = String::new();
+        loop {
+            let Some(c) = self.char_at_cursor(&cursor) else {
+                return (TokenValue::UnterminatedString, cursor);
+            };
+            cursor = if c == langchars::NEW_LINE { cursor.next_line() } else { cursor.next_column() };
+            match c {
+                langchars::STRING_DELIMITER => return (TokenValue::StringLiteral(value), cursor),
+                langchars::ESCAPE => {
+                    let Some(escaped) = self.char_at_cursor(&cursor) else {
+                        return (TokenValue::UnterminatedString, cursor);
+                    };
+                    cursor = if escaped == langchars::NEW_LINE { cursor.next_line() } else { cursor.next_column() };
+                    value.push(match escaped {
+                        'n' => '\n',
+                        't' => '\t',
+                        'r' => '\r',
+                        //quotes, backslashes and anything unknown stand for themselves
+                        other => other,
+                    });
+                }
+                c => value.push(c),
+            }
+        }
+    }
+
     pub fn skip_comment(&self, start: &Cursor) -> (Cursor) {
         let mut cursor = start.clone();
         if let Some(c) = self.char_at_cursor(&cursor) {
@@ -142,13 +236,31 @@ impl<'t> Lexer<'t>{
     pub fn next_token(&self, cursor: &Cursor) -> (Token, Cursor){
         if let Some(char) = self.char_at_cursor(&cursor){
             match char{
-                langchars::SPACE | langchars::TAB => { self.next_token(&cursor.next_column()) }
+                langchars::SPACE | langchars::TAB | langchars::CARRIAGE_RETURN => { self.next_token(&cursor.next_column()) }
                 langchars::NEW_LINE => { self.next_token(&cursor.next_line()) }
-                langchars::PARENTHESIS_OPEN => { (Token{cursor: cursor.next_column(), value: TokenValue::ParenthesisOpen}, cursor.next_column()) }
-                langchars::PARENTHESIS_CLOSE => { (Token{cursor: cursor.next_column(), value: TokenValue::ParenthesisClose}, cursor.next_column()) }
-                langchars::BRACKET_OPEN => { (Token{cursor: cursor.next_column(), value: TokenValue::BracketOpen}, cursor.next_column()) }
-                langchars::BRACKET_CLOSE => { (Token{cursor: cursor.next_column(), value: TokenValue::BracketClose}, cursor.next_column()) }
+                langchars::PARENTHESIS_OPEN => { (Token{cursor: cursor.clone(), value: TokenValue::ParenthesisOpen}, cursor.next_column()) }
+                langchars::PARENTHESIS_CLOSE => { (Token{cursor: cursor.clone(), value: TokenValue::ParenthesisClose}, cursor.next_column()) }
+                langchars::BRACKET_OPEN => { (Token{cursor: cursor.clone(), value: TokenValue::BracketOpen}, cursor.next_column()) }
+                langchars::BRACKET_CLOSE => { (Token{cursor: cursor.clone(), value: TokenValue::BracketClose}, cursor.next_column()) }
+                langchars::COMMENT if self.keep_comments => {
+                    let after_cursor = self.skip_comment(cursor);
+                    let text = self.txt_buffer.chars()
+                        .skip(cursor.abs_position)
+                        .take(after_cursor.abs_position - cursor.abs_position)
+                        .collect::<String>();
+                    (Token{cursor: cursor.clone(), value: TokenValue::Comment(text)}, after_cursor)
+                }
                 langchars::COMMENT => {self.next_token(&self.skip_comment(cursor))}
+                langchars::STRING_DELIMITER => {
+                    let (value, after_cursor) = self.read_string(cursor);
+                    (
+                        Token{
+                            cursor: Cursor{ _reach: Some(after_cursor.abs_position-cursor.abs_position), ..*cursor},
+                            value
+                        },
+                        after_cursor
+                    )
+                }
                 _ => {
                     let (ident_token, after_cursor) = self.read_identifier(cursor);
                     let TokenValue::Identifier(i) = ident_token else {panic!("didn't receive identifier")};
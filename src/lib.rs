@@ -1,9 +1,22 @@
+//lets the code generated by kisp-macros refer to ::kisp from inside this crate too
+extern crate self as kisp;
+
 pub mod lexer;
 pub mod parser;
 pub mod ast;
 pub mod interpreter;
 pub mod scope;
+pub mod runtime;
 pub mod stdlib;
 pub mod value;
 pub mod testutils;
 pub mod stacktrace;
+pub mod debugger;
+pub mod profiler;
+pub mod formatter;
+pub mod json;
+pub mod lsp;
+pub mod resolver;
+pub mod highlight;
+
+pub use kisp_macros::builtin;
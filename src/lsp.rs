@@ -0,0 +1,403 @@
+use std::collections::HashMap;
+use std::io;
+use std::io::{BufRead, Write};
+
+use crate::ast::{parameter_list, PosExpression, SExpression};
+use crate::json;
+use crate::json::JsonValue;
+use crate::lexer::{Cursor, Lexer};
+use crate::parser::{parse, ParserError};
+use crate::resolver;
+use crate::resolver::Severity;
+use crate::stdlib::std_lib_functions;
+
+//language server speaking json-rpc, stdio in practice but any reader/writer pair works
+
+const METHOD_NOT_FOUND: f64 = -32601.0;
+const SYNC_FULL: f64 = 1.0;
+const COMPLETION_KIND_FUNCTION: f64 = 3.0;
+const SYMBOL_KIND_FUNCTION: f64 = 12.0;
+const SEVERITY_ERROR: f64 = 1.0;
+const SEVERITY_WARNING: f64 = 2.0;
+
+pub fn read_message(input: &mut impl BufRead) -> io::Result<Option<JsonValue>> {
+    let mut length: Option<usize> = None;
+    loop {
+        let mut header = String::new();
+        if input.read_line(&mut header)? == 0 {
+            return Ok(None);
+        }
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some(value) = header.strip_prefix("Content-Length:") {
+            length = value.trim().parse().ok();
+        }
+    }
+    let length = length.ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "missing Content-Length"))?;
+    let mut body = vec![0; length];
+    input.read_exact(&mut body)?;
+    let text = String::from_utf8(body).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    json::parse(&text)
+        .map(Some)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))
+}
+
+pub fn write_message(output: &mut impl Write, message: &JsonValue) -> io::Result<()> {
+    let body = message.to_string();
+    write!(output, "Content-Length: {}\r\n\r\n{}", body.len(), body)?;
+    output.flush()
+}
+
+fn position(line: usize, column: usize) -> JsonValue {
+    JsonValue::object(vec![
+        ("line", JsonValue::Number(line.saturating_sub(1) as f64)),
+        ("character", JsonValue::Number(column.saturating_sub(1) as f64)),
+    ])
+}
+
+fn range(cursor: &Cursor, len: usize) -> JsonValue {
+    JsonValue::object(vec![
+        ("start", position(cursor.line(), cursor.column())),
+        ("end", position(cursor.line(), cursor.column() + len)),
+    ])
+}
+
+fn diagnostic(cursor: &Cursor, len: usize, severity: f64, message: String) -> JsonValue {
+    JsonValue::object(vec![
+        ("range", range(cursor, len)),
+        ("severity", JsonValue::Number(severity)),
+        ("source", JsonValue::string("kisp")),
+        ("message", JsonValue::string(message)),
+    ])
+}
+
+fn location(uri: &str, cursor: &Cursor, len: usize) -> JsonValue {
+    JsonValue::object(vec![("uri", JsonValue::string(uri)), ("range", range(cursor, len))])
+}
+
+fn parse_document(text: &str) -> Result<PosExpression, ParserError> {
+    parse(&mut Lexer::from_text(text).into_iter())
+}
+
+fn symbol_name(exp: &PosExpression) -> Option<&str> {
+    match &exp.exp {
+        SExpression::Symbol(s) => Some(s.as_str()),
+        _ => None,
+    }
+}
+
+//children of a list headed by the given symbol, e.g. (fn name [args] body)
+fn form<'a>(exp: &'a PosExpression, head: &str) -> Option<&'a [PosExpression]> {
+    match &exp.exp {
+        SExpression::List(children) if children.first().and_then(symbol_name) == Some(head) => Some(&children[1..]),
+        _ => None,
+    }
+}
+
+//expressions from the root down to the symbol under the cursor
+fn symbol_path<'a>(exp: &'a PosExpression, line: usize, column: usize, path: &mut Vec<&'a PosExpression>) -> bool {
+    match &exp.exp {
+        SExpression::Symbol(s) if exp.cursor.line() == line
+            && (exp.cursor.column()..exp.cursor.column() + s.chars().count()).contains(&column) => {
+            path.push(exp);
+            true
+        }
+        SExpression::List(children) | SExpression::Block(children) => {
+            path.push(exp);
+            if children.iter().any(|c| symbol_path(c, line, column, path)) {
+                return true;
+            }
+            path.pop();
+            false
+        }
+        _ => false,
+    }
+}
+
+fn parameter<'a>(params: Option<&'a PosExpression>, name: &str) -> Option<&'a PosExpression> {
+    parameter_list(params?)?.into_iter()
+        .find(|p| p.ident() == name)
+        .map(|p| p.name)
+}
+
+fn before(a: &Cursor, b: &Cursor) -> bool {
+    (a.line(), a.column()) <= (b.line(), b.column())
+}
+
+//walk outwards from the usage, parameters first, then fn/let bindings of enclosing blocks
+fn definition<'a>(path: &[&'a PosExpression], name: &str) -> Option<&'a PosExpression> {
+    let usage = &path.last()?.cursor;
+    path.iter().rev().skip(1).find_map(|ancestor| {
+        if let Some(rest) = form(ancestor, "fn") {
+            return parameter(rest.get(1), name);
+        }
+        if let Some(rest) = form(ancestor, "lambda") {
+            return parameter(rest.first(), name);
+        }
+        let SExpression::Block(children) = &ancestor.exp else { return None };
+        let bindings: Vec<&PosExpression> = children.iter()
+            .filter_map(|c| form(c, "fn").or_else(|| form(c, "let")))
+            .filter_map(|rest| rest.first())
+            .filter(|n| symbol_name(n) == Some(name))
+            .collect();
+        bindings.iter().rev().find(|n| before(&n.cursor, usage)).or(bindings.first()).copied()
+    })
+}
+
+fn signature(path: &[&PosExpression], definition: &PosExpression) -> Option<String> {
+    //the definition name sits right after `fn`, find its form among the enclosing blocks
+    path.iter()
+        .filter_map(|a| match &a.exp {
+            SExpression::Block(children) => Some(children),
+            _ => None,
+        })
+        .flatten()
+        .filter_map(|c| form(c, "fn"))
+        .find(|rest| rest.first().map(|n| n.cursor.abs_position()) == Some(definition.cursor.abs_position()))
+        .map(|rest| {
+            let signature = format!("(fn {} {})", rest[0].exp, rest.get(1).map(|a| a.exp.to_string()).unwrap_or_default());
+            match (rest.get(2).map(|d| &d.exp), rest.len()) {
+                (Some(SExpression::String(doc)), 4) => format!("{}\n\n{}", signature, doc),
+                _ => signature,
+            }
+        })
+}
+
+//signature as a code block, the description and examples below it
+fn builtin_hover(name: &str, doc: Option<&String>) -> String {
+    match doc.and_then(|d| d.split_once('\n')) {
+        Some((signature, rest)) => format!("`{}` builtin function\n```\n{}\n```\n{}", name, signature, rest.replace('\n', "  \n")),
+        None => format!("`{}` builtin function", name),
+    }
+}
+
+pub struct Server{
+    documents: HashMap<String, String>,
+    builtins: Vec<String>,
+    docs: HashMap<String, String>,
+    shutdown_requested: bool,
+    exited: bool,
+}
+
+impl Default for Server{
+    fn default() -> Self {
+        Server::new()
+    }
+}
+
+impl Server{
+    pub fn new() -> Server {
+        let functions = std_lib_functions();
+        let docs = functions.iter().filter_map(|b| Some((b.name.clone(), b.doc.clone()?))).collect();
+        let mut builtins: Vec<String> = functions.into_iter().map(|b| b.name).collect();
+        builtins.push("true".to_string());
+        builtins.sort();
+        builtins.dedup();
+        Server{documents: HashMap::new(), builtins, docs, shutdown_requested: false, exited: false}
+    }
+
+    pub fn exited(&self) -> bool {
+        self.exited
+    }
+
+    //clients are supposed to request a shutdown before telling us to exit
+    pub fn shutdown_requested(&self) -> bool {
+        self.shutdown_requested
+    }
+
+    pub fn run(&mut self, input: &mut impl BufRead, output: &mut impl Write) -> io::Result<()> {
+        while !self.exited {
+            let Some(message) = read_message(input)? else { break };
+            for reply in self.handle(&message) {
+                write_message(output, &reply)?;
+            }
+        }
+        Ok(())
+    }
+
+    //answers a single message, returning responses and notifications to send back
+    pub fn handle(&mut self, message: &JsonValue) -> Vec<JsonValue> {
+        let method = message.get("method").and_then(|m| m.as_str()).unwrap_or("");
+        let params = message.get("params").cloned().unwrap_or(JsonValue::Null);
+        let id = message.get("id").cloned();
+        let result = match method {
+            "initialize" => Some(self.initialize()),
+            "shutdown" => {
+                self.shutdown_requested = true;
+                Some(JsonValue::Null)
+            }
+            "exit" => {
+                self.exited = true;
+                None
+            }
+            "textDocument/didOpen" => {
+                let document = params.get("textDocument");
+                let uri = document.and_then(|d| d.get("uri")).and_then(|u| u.as_str()).unwrap_or("");
+                let text = document.and_then(|d| d.get("text")).and_then(|t| t.as_str()).unwrap_or("");
+                return self.update(uri, text.to_string());
+            }
+            "textDocument/didChange" => {
+                let uri = Self::uri(&params).unwrap_or("").to_string();
+                let text = match params.get("contentChanges") {
+                    Some(JsonValue::Array(changes)) => changes.last().and_then(|c| c.get("text")).and_then(|t| t.as_str()),
+                    _ => None,
+                };
+                return match text {
+                    Some(text) => self.update(&uri, text.to_string()),
+                    None => vec![],
+                };
+            }
+            "textDocument/didClose" => {
+                let uri = Self::uri(&params).unwrap_or("").to_string();
+                self.documents.remove(&uri);
+                return vec![Self::diagnostics_notification(&uri, vec![])];
+            }
+            "textDocument/completion" => Some(self.completion()),
+            "textDocument/hover" => Some(self.hover(&params)),
+            "textDocument/definition" => Some(self.definition(&params)),
+            "textDocument/documentSymbol" => Some(self.document_symbols(&params)),
+            _ => None,
+        };
+        match (id, result) {
+            (Some(id), Some(result)) => vec![JsonValue::object(vec![
+                ("jsonrpc", JsonValue::string("2.0")),
+                ("id", id),
+                ("result", result),
+            ])],
+            (Some(id), None) if method != "exit" => vec![JsonValue::object(vec![
+                ("jsonrpc", JsonValue::string("2.0")),
+                ("id", id),
+                ("error", JsonValue::object(vec![
+                    ("code", JsonValue::Number(METHOD_NOT_FOUND)),
+                    ("message", JsonValue::string(format!("method not found: {}", method))),
+                ])),
+            ])],
+            _ => vec![],
+        }
+    }
+
+    fn initialize(&self) -> JsonValue {
+        JsonValue::object(vec![
+            ("capabilities", JsonValue::object(vec![
+                ("textDocumentSync", JsonValue::Number(SYNC_FULL)),
+                ("completionProvider", JsonValue::object(vec![])),
+                ("hoverProvider", JsonValue::Bool(true)),
+                ("definitionProvider", JsonValue::Bool(true)),
+                ("documentSymbolProvider", JsonValue::Bool(true)),
+            ])),
+            ("serverInfo", JsonValue::object(vec![("name", JsonValue::string("kisp-lsp"))])),
+        ])
+    }
+
+    fn uri(params: &JsonValue) -> Option<&str> {
+        params.get("textDocument").and_then(|d| d.get("uri")).and_then(|u| u.as_str())
+    }
+
+    fn update(&mut self, uri: &str, text: String) -> Vec<JsonValue> {
+        let diagnostics = match parse_document(&text) {
+            Ok(ast) => resolver::check(&ast).into_iter()
+                .map(|d| {
+                    let severity = match d.severity() {
+                        Severity::Error => SEVERITY_ERROR,
+                        Severity::Warning => SEVERITY_WARNING,
+                    };
+                    diagnostic(&d.cursor, d.cursor.reach().unwrap_or(1), severity, d.to_string())
+                })
+                .collect(),
+            Err(e) => {
+                let cursor = match &e {
+                    ParserError::UnexpectedToken(c)
+                    | ParserError::NoMatchingParser(c)
+                    | ParserError::UnclosedParenthesis(c)
+                    | ParserError::UnterminatedString(c) => c.clone(),
+                };
+                vec![diagnostic(&cursor, 1, SEVERITY_ERROR, e.to_string())]
+            }
+        };
+        self.documents.insert(uri.to_string(), text);
+        vec![Self::diagnostics_notification(uri, diagnostics)]
+    }
+
+    fn diagnostics_notification(uri: &str, diagnostics: Vec<JsonValue>) -> JsonValue {
+        JsonValue::object(vec![
+            ("jsonrpc", JsonValue::string("2.0")),
+            ("method", JsonValue::string("textDocument/publishDiagnostics")),
+            ("params", JsonValue::object(vec![
+                ("uri", JsonValue::string(uri)),
+                ("diagnostics", JsonValue::Array(diagnostics)),
+            ])),
+        ])
+    }
+
+    fn completion(&self) -> JsonValue {
+        JsonValue::Array(
+            self.builtins.iter()
+                .map(|name| JsonValue::object(vec![
+                    ("label", JsonValue::string(name.as_str())),
+                    ("kind", JsonValue::Number(COMPLETION_KIND_FUNCTION)),
+                    ("detail", JsonValue::string("builtin")),
+                ]))
+                .collect()
+        )
+    }
+
+    //parsed document plus the path to the symbol the request points at
+    fn with_symbol_at<T>(&self, params: &JsonValue, f: impl FnOnce(&str, &[&PosExpression]) -> Option<T>) -> Option<T> {
+        let uri = Self::uri(params)?;
+        let ast = parse_document(self.documents.get(uri)?).ok()?;
+        let pos = params.get("position")?;
+        let line = pos.get("line")?.as_usize()? + 1;
+        let column = pos.get("character")?.as_usize()? + 1;
+        let mut path = Vec::new();
+        if !symbol_path(&ast, line, column, &mut path) {
+            return None;
+        }
+        f(uri, &path)
+    }
+
+    fn hover(&self, params: &JsonValue) -> JsonValue {
+        self.with_symbol_at(params, |_, path| {
+            let name = symbol_name(path.last()?)?;
+            let text = match definition(path, name) {
+                Some(def) => signature(path, def).unwrap_or_else(|| format!("`{}` binding", name)),
+                None if self.builtins.iter().any(|b| b == name) => builtin_hover(name, self.docs.get(name)),
+                None => return None,
+            };
+            Some(JsonValue::object(vec![
+                ("contents", JsonValue::object(vec![
+                    ("kind", JsonValue::string("markdown")),
+                    ("value", JsonValue::string(text)),
+                ])),
+            ]))
+        }).unwrap_or(JsonValue::Null)
+    }
+
+    fn definition(&self, params: &JsonValue) -> JsonValue {
+        self.with_symbol_at(params, |uri, path| {
+            let name = symbol_name(path.last()?)?;
+            let def = definition(path, name)?;
+            Some(location(uri, &def.cursor, name.chars().count()))
+        }).unwrap_or(JsonValue::Null)
+    }
+
+    fn document_symbols(&self, params: &JsonValue) -> JsonValue {
+        let Some(uri) = Self::uri(params) else { return JsonValue::Null };
+        let Some(Ok(PosExpression{exp: SExpression::Block(top_level), ..})) = self.documents.get(uri).map(|t| parse_document(t)) else {
+            return JsonValue::Array(vec![]);
+        };
+        JsonValue::Array(
+            top_level.iter()
+                .filter_map(|e| form(e, "fn"))
+                .filter_map(|rest| rest.first())
+                .filter_map(|n| symbol_name(n).map(|s| (s, &n.cursor)))
+                .map(|(name, cursor)| JsonValue::object(vec![
+                    ("name", JsonValue::string(name)),
+                    ("kind", JsonValue::Number(SYMBOL_KIND_FUNCTION)),
+                    ("location", location(uri, cursor, name.chars().count())),
+                ]))
+                .collect()
+        )
+    }
+}
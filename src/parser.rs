@@ -1,3 +1,4 @@
+use std::fmt::{Display, Formatter};
 use crate::ast;
 use crate::lexer::{Cursor, Token, TokenValue};
 use crate::ast::{PosExpression, SExpression};
@@ -7,8 +8,8 @@ use crate::lexer::TokenStream;
 pub enum ParserError{
     UnexpectedToken(Cursor),
     NoMatchingParser(Cursor),
-    UnclosedParenthesis,
-    //UnclosedParenthesis(Cursor),
+    UnclosedParenthesis(Cursor),
+    UnterminatedString(Cursor),
 }
 
 pub type ParserResult = Result<Option<ast::PosExpression>, ParserError>;
@@ -44,13 +45,20 @@ fn parse_s_expression(stream: &mut TokenStream) -> ParserResult {
 
 fn parse_atomic(stream: &mut TokenStream) -> ParserResult{
     let stream = stream;
-    match stream.next_if(|token| matches!(token.value, TokenValue::NumericToken(_)) ||matches!(token.value, TokenValue::Identifier(_))) {
+    match stream.next_if(|token| matches!(token.value, TokenValue::NumericToken(_) | TokenValue::Identifier(_) | TokenValue::Keyword(_) | TokenValue::StringLiteral(_) | TokenValue::UnterminatedString)) {
         Some(Token {value: TokenValue::Identifier(ident), cursor}) => {
             Ok(Some(PosExpression{cursor, exp: SExpression::Symbol(ident)}))
         },
         Some(Token{value: TokenValue::NumericToken(i), cursor}) => {
             Ok(Some(PosExpression{cursor, exp: SExpression::Number(i)}))
         }
+        Some(Token{value: TokenValue::Keyword(k), cursor}) => {
+            Ok(Some(PosExpression{cursor, exp: SExpression::Keyword(k)}))
+        }
+        Some(Token{value: TokenValue::StringLiteral(s), cursor}) => {
+            Ok(Some(PosExpression{cursor, exp: SExpression::String(s)}))
+        }
+        Some(Token{value: TokenValue::UnterminatedString, cursor}) => Err(ParserError::UnterminatedString(cursor)),
         _ => { Ok(None) }
     }
 }
@@ -85,11 +93,21 @@ fn parse_listy(stream: &mut TokenStream, open: TokenValue, close: TokenValue) ->
     if stream.peek().unwrap().value != open {
         return Ok(None);
     }
-    stream.next(); //discard open
+    let open_cursor = stream.next().unwrap().cursor; //discard open
     let inner = parse_list_iter(stream, Vec::new())?;
     if stream.peek().unwrap().value != close {
-        return Err(ParserError::UnclosedParenthesis); //TODO: not generic enough
+        return Err(ParserError::UnclosedParenthesis(open_cursor)); //TODO: not generic enough
     }
     stream.next(); //discard close
-    Ok(Some((inner, Cursor::new())))
-}
\ No newline at end of file
+    Ok(Some((inner, open_cursor)))
+}
+impl Display for ParserError{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParserError::UnexpectedToken(c) => f.write_fmt(format_args!("unexpected token at {}:{}", c.line(), c.column())),
+            ParserError::NoMatchingParser(c) => f.write_fmt(format_args!("unexpected token at {}:{}", c.line(), c.column())),
+            ParserError::UnclosedParenthesis(c) => f.write_fmt(format_args!("unclosed parenthesis opened at {}:{}", c.line(), c.column())),
+            ParserError::UnterminatedString(c) => f.write_fmt(format_args!("unterminated string starting at {}:{}", c.line(), c.column())),
+        }
+    }
+}
@@ -0,0 +1,119 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use crate::value::list;
+
+//what one function or lambda cost over all of its calls
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FunctionProfile {
+    pub calls: u64,
+    //including the functions it called, recursive calls are only counted once
+    pub inclusive: Duration,
+    pub exclusive: Duration,
+    //list cells allocated while it ran
+    pub inclusive_cells: usize,
+    pub exclusive_cells: usize,
+}
+
+#[derive(Debug)]
+struct Frame {
+    label: String,
+    started: Instant,
+    cells_at_start: usize,
+    //spent in calls made from this one
+    children: Duration,
+    children_cells: usize,
+}
+
+//records every function and lambda call while installed on a runtime, keyed by Callable::label
+#[derive(Debug, Default)]
+pub struct Profiler {
+    functions: RefCell<HashMap<String, FunctionProfile>>,
+    stack: RefCell<Vec<Frame>>,
+    //exclusive time per call stack, outermost first and joined with ;
+    stacks: RefCell<HashMap<String, Duration>>,
+}
+
+impl Profiler {
+    pub fn new() -> Profiler {
+        Profiler::default()
+    }
+
+    pub fn enter(&self, label: String) {
+        self.stack.borrow_mut().push(Frame{
+            label,
+            started: Instant::now(),
+            cells_at_start: list::allocated_cells(),
+            children: Duration::ZERO,
+            children_cells: 0,
+        });
+    }
+
+    //a self tail call returns right away and the loop runs in the frame that made the first call,
+    //so those calls are counted but their time belongs to the first one
+    pub fn leave(&self) {
+        let mut stack = self.stack.borrow_mut();
+        let Some(frame) = stack.pop() else { return };
+        let elapsed = frame.started.elapsed();
+        let cells = list::allocated_cells().saturating_sub(frame.cells_at_start);
+        let exclusive = elapsed.saturating_sub(frame.children);
+        let recursive = stack.iter().any(|f| f.label == frame.label);
+
+        let mut functions = self.functions.borrow_mut();
+        let profile = functions.entry(frame.label.clone()).or_default();
+        profile.calls += 1;
+        profile.exclusive += exclusive;
+        profile.exclusive_cells += cells.saturating_sub(frame.children_cells);
+        if !recursive {
+            profile.inclusive += elapsed;
+            profile.inclusive_cells += cells;
+        }
+
+        let path = stack.iter().map(|f| f.label.as_str()).chain([frame.label.as_str()]).collect::<Vec<&str>>().join(";");
+        *self.stacks.borrow_mut().entry(path).or_default() += exclusive;
+        if let Some(parent) = stack.last_mut() {
+            parent.children += elapsed;
+            parent.children_cells += cells;
+        }
+    }
+
+    pub fn clear(&self) {
+        self.functions.borrow_mut().clear();
+        self.stack.borrow_mut().clear();
+        self.stacks.borrow_mut().clear();
+    }
+
+    //most exclusive time first
+    pub fn functions(&self) -> Vec<(String, FunctionProfile)> {
+        let mut functions: Vec<(String, FunctionProfile)> = self.functions.borrow().iter()
+            .map(|(label, profile)| (label.clone(), profile.clone()))
+            .collect();
+        functions.sort_by(|(a_label, a), (b_label, b)| b.exclusive.cmp(&a.exclusive).then_with(|| a_label.cmp(b_label)));
+        functions
+    }
+
+    pub fn report(&self) -> String {
+        let functions = self.functions();
+        let width = functions.iter().map(|(label, _)| label.chars().count()).chain(["function".len()]).max().unwrap_or_default();
+        let mut report = format!("{:<width$} {:>8} {:>12} {:>12} {:>10} {:>10}\n", "function", "calls", "inclusive", "exclusive", "incl cells", "excl cells");
+        for (label, p) in functions {
+            report.push_str(&format!("{:<width$} {:>8} {:>12} {:>12} {:>10} {:>10}\n",
+                label, p.calls, millis(p.inclusive), millis(p.exclusive), p.inclusive_cells, p.exclusive_cells));
+        }
+        report
+    }
+
+    //`outer;inner microseconds` lines, the input flamegraph.pl and inferno expect
+    pub fn folded(&self) -> String {
+        let mut lines: Vec<String> = self.stacks.borrow().iter()
+            .map(|(path, time)| format!("{} {}\n", path, time.as_micros()))
+            .collect();
+        lines.sort();
+        lines.concat()
+    }
+}
+
+fn millis(duration: Duration) -> String {
+    format!("{:.3}ms", duration.as_secs_f64() * 1000.0)
+}
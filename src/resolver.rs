@@ -0,0 +1,370 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt::{Display, Formatter};
+
+use crate::ast::{parameter_list, ParameterSpec, PosExpression, SExpression};
+use crate::lexer::{Cursor, Lexer};
+use crate::parser::{parse, ParserError};
+use crate::stdlib::std_lib_functions;
+
+//static pass over the ast, finds mistakes that would otherwise only show up once a branch actually runs
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Severity{
+    Error,
+    Warning,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DiagnosticKind{
+    UnboundSymbol(String),
+    //a fn named later in the same block, not bound yet when this runs
+    UsedBeforeDefinition(String),
+    ShadowedBuiltin(String),
+    UnusedBinding(String),
+    WrongArity{name: String, min: usize, max: Option<usize>, actual: usize},
+}
+
+#[derive(Debug, Clone)]
+pub struct Diagnostic{
+    pub kind: DiagnosticKind,
+    pub cursor: Cursor,
+}
+
+impl Diagnostic{
+    pub fn severity(&self) -> Severity {
+        match self.kind {
+            DiagnosticKind::UnboundSymbol(_) | DiagnosticKind::UsedBeforeDefinition(_) | DiagnosticKind::WrongArity{..} => Severity::Error,
+            DiagnosticKind::ShadowedBuiltin(_) | DiagnosticKind::UnusedBinding(_) => Severity::Warning,
+        }
+    }
+}
+
+impl Display for Diagnostic{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match &self.kind {
+            DiagnosticKind::UnboundSymbol(name) => f.write_fmt(format_args!("unbound symbol `{}`", name)),
+            DiagnosticKind::UsedBeforeDefinition(name) => f.write_fmt(format_args!("`{}` is used before its fn form", name)),
+            DiagnosticKind::ShadowedBuiltin(name) => f.write_fmt(format_args!("`{}` shadows a builtin", name)),
+            DiagnosticKind::UnusedBinding(name) => f.write_fmt(format_args!("`{}` is never used", name)),
+            DiagnosticKind::WrongArity{name, min, max: Some(max), actual} if min == max =>
+                f.write_fmt(format_args!("`{}` expects {} argument(s), got {}", name, min, actual)),
+            DiagnosticKind::WrongArity{name, min, max: Some(max), actual} =>
+                f.write_fmt(format_args!("`{}` expects {} to {} arguments, got {}", name, min, max, actual)),
+            DiagnosticKind::WrongArity{name, min, max: None, actual} =>
+                f.write_fmt(format_args!("`{}` expects at least {} argument(s), got {}", name, min, actual)),
+        }
+    }
+}
+
+struct Binding{
+    name: String,
+    cursor: Cursor,
+    used: bool,
+    //only known for user functions
+    arity: Option<Arity>,
+    //a fn form further down the block, only function bodies may refer to it until it is reached
+    pending: bool,
+    //pending names the function body refers to, they have to be defined by the time it is called
+    needs: Vec<String>,
+}
+
+#[derive(Clone)]
+struct Arity{
+    min: usize,
+    max: Option<usize>,
+    keywords: Vec<String>,
+    //builtins count a `:keyword value` pair as one argument
+    pairs: bool,
+}
+
+impl Arity{
+    //arguments left over once the `:key value` pairs this function declares are taken out, or counted once for builtins
+    fn positional(&self, args: &[PosExpression]) -> usize {
+        let mut count = 0;
+        let mut iter = args.iter();
+        while let Some(arg) = iter.next() {
+            match &arg.exp {
+                SExpression::Keyword(k) if self.keywords.contains(k) => {
+                    iter.next();
+                    count += usize::from(self.pairs);
+                }
+                _ => count += 1,
+            }
+        }
+        count
+    }
+}
+
+struct Frame{
+    bindings: Vec<Binding>,
+    //top level definitions are the program's api, nobody expects them to be used locally
+    report_unused: bool,
+    //parameters of a fn or lambda, whatever is bound outside of it is only looked up once it's called
+    function: bool,
+}
+
+struct Resolver{
+    //with the arity of the builtins that record one
+    builtins: HashMap<String, Option<Arity>>,
+    frames: Vec<Frame>,
+    //fn declarations already bound ahead of time by their block
+    hoisted: HashSet<usize>,
+    //pending names used by each function body being visited, innermost last
+    needs: Vec<Vec<String>>,
+    diagnostics: Vec<Diagnostic>,
+}
+
+fn symbol(exp: &PosExpression) -> Option<&String> {
+    match &exp.exp {
+        SExpression::Symbol(s) => Some(s),
+        _ => None,
+    }
+}
+
+fn parameters(exp: Option<&PosExpression>) -> Option<Vec<ParameterSpec<'_>>> {
+    exp.and_then(parameter_list)
+}
+
+fn arity(params: &[ParameterSpec]) -> Arity {
+    let keywords: Vec<String> = params.iter().filter(|p| p.keyword).map(|p| p.ident().to_string()).collect();
+    let min = params.iter().filter(|p| p.default.is_none() && !p.rest).count();
+    let max = match params.iter().any(|p| p.rest) {
+        true => None,
+        false => Some(params.len() - keywords.len()),
+    };
+    Arity{min, max, keywords, pairs: false}
+}
+
+impl Resolver{
+    fn push(&mut self, report_unused: bool, function: bool) {
+        self.frames.push(Frame{bindings: vec![], report_unused, function});
+    }
+
+    fn pop(&mut self) {
+        let frame = self.frames.pop().unwrap();
+        if !frame.report_unused {
+            return;
+        }
+        for binding in frame.bindings.into_iter().filter(|b| !b.used && !b.name.starts_with('_')) {
+            self.diagnostics.push(Diagnostic{kind: DiagnosticKind::UnusedBinding(binding.name), cursor: binding.cursor});
+        }
+    }
+
+    fn declare(&mut self, name: &PosExpression, arity: Option<Arity>) {
+        let Some(ident) = symbol(name) else { return };
+        if self.builtins.contains_key(ident) {
+            self.diagnostics.push(Diagnostic{kind: DiagnosticKind::ShadowedBuiltin(ident.clone()), cursor: name.cursor.clone()});
+        }
+        let frame = self.frames.last_mut().unwrap();
+        frame.bindings.push(Binding{name: ident.clone(), cursor: name.cursor.clone(), used: false, arity, pending: false, needs: vec![]});
+    }
+
+    fn lookup(&mut self, name: &str) -> Option<&mut Binding> {
+        self.frames.iter_mut().rev()
+            .find_map(|f| f.bindings.iter_mut().rev().find(|b| b.name == name))
+    }
+
+    //the binding made by the fn form whose name is at this position
+    fn declared_at(&mut self, position: usize) -> Option<&mut Binding> {
+        self.frames.iter_mut().rev()
+            .find_map(|f| f.bindings.iter_mut().find(|b| b.cursor.abs_position() == position))
+    }
+
+    //whether the innermost binding of name is in a frame outside of every function being visited
+    fn direct(&self, name: &str) -> bool {
+        self.frames.iter().rev()
+            .take_while(|f| !f.bindings.iter().any(|b| b.name == name))
+            .all(|f| !f.function)
+    }
+
+    //names still pending once name and everything it calls ran, following the needs of functions
+    fn pending_needs(&mut self, name: &str) -> Vec<String> {
+        let mut seen = vec![name.to_string()];
+        let mut pending = vec![];
+        let mut index = 0;
+        while let Some(current) = seen.get(index).cloned() {
+            index += 1;
+            let Some(binding) = self.lookup(&current) else { continue };
+            if binding.pending {
+                pending.push(current);
+                continue;
+            }
+            for need in binding.needs.clone() {
+                if !seen.contains(&need) {
+                    seen.push(need);
+                }
+            }
+        }
+        pending
+    }
+
+    //the arity of the function if it is known, a call also runs whatever the function body needs
+    fn resolve(&mut self, name: &str, cursor: &Cursor, call: bool) -> Option<Arity> {
+        let Some(binding) = self.lookup(name) else {
+            return match self.builtins.get(name) {
+                Some(arity) => arity.clone(),
+                None => {
+                    self.diagnostics.push(Diagnostic{kind: DiagnosticKind::UnboundSymbol(name.to_string()), cursor: cursor.clone()});
+                    None
+                }
+            };
+        };
+        binding.used = true;
+        let arity = binding.arity.clone();
+        let pending = match (call, binding.pending) {
+            (true, _) => self.pending_needs(name),
+            (false, true) => vec![name.to_string()],
+            (false, false) => vec![],
+        };
+        if self.direct(name) {
+            for name in pending {
+                self.diagnostics.push(Diagnostic{kind: DiagnosticKind::UsedBeforeDefinition(name), cursor: cursor.clone()});
+            }
+        } else {
+            for needs in self.needs.iter_mut() {
+                needs.extend(pending.iter().cloned());
+            }
+        }
+        arity
+    }
+
+    //special forms only count as such while they still refer to the builtin
+    fn is_builtin(&mut self, name: &str) -> bool {
+        self.builtins.contains_key(name) && self.lookup(name).is_none()
+    }
+
+    fn visit_block(&mut self, children: &[PosExpression], report_unused: bool) {
+        self.push(report_unused, false);
+        for child in children {
+            if let SExpression::List(form) = &child.exp {
+                if form.first().and_then(symbol).map(|s| s == "fn").unwrap_or(false) {
+                    if let (Some(name), Some(params)) = (form.get(1), parameters(form.get(2))) {
+                        self.declare(name, Some(arity(&params)));
+                        self.hoisted.insert(name.cursor.abs_position());
+                        if let Some(binding) = self.declared_at(name.cursor.abs_position()) {
+                            binding.pending = true;
+                        }
+                    }
+                }
+            }
+        }
+        children.iter().for_each(|c| self.visit(c));
+        self.pop();
+    }
+
+    //the pending names the function needs
+    fn visit_function(&mut self, params: &[ParameterSpec], body: &[PosExpression]) -> Vec<String> {
+        self.needs.push(vec![]);
+        self.push(true, true);
+        for param in params {
+            //defaults run in the function scope, after the parameters before them are bound
+            if let Some(default) = param.default {
+                self.visit(default);
+            }
+            self.declare(param.name, None);
+        }
+        body.iter().for_each(|b| self.visit(b));
+        self.pop();
+        self.needs.pop().unwrap_or_default()
+    }
+
+    fn visit_list(&mut self, children: &[PosExpression]) {
+        let Some((head, args)) = children.split_first() else { return };
+        let head_name = symbol(head).cloned();
+        match head_name.as_deref() {
+            Some("fn") if self.is_builtin("fn") => {
+                if let (Some(name), Some(params)) = (args.first(), parameters(args.get(1))) {
+                    let position = name.cursor.abs_position();
+                    if !self.hoisted.contains(&position) {
+                        self.declare(name, Some(arity(&params)));
+                    }
+                    if let Some(binding) = self.declared_at(position) {
+                        binding.pending = false;
+                    }
+                    let needs = self.visit_function(&params, &args[2..]);
+                    if let Some(binding) = self.declared_at(position) {
+                        binding.needs = needs;
+                    }
+                    return;
+                }
+            }
+            Some("lambda") if self.is_builtin("lambda") => {
+                if let Some(params) = parameters(args.first()) {
+                    self.visit_function(&params, &args[1..]);
+                    return;
+                }
+            }
+            Some("let") if self.is_builtin("let") => {
+                if let Some(name) = args.first().filter(|n| symbol(n).is_some()) {
+                    args[1..].iter().for_each(|a| self.visit(a));
+                    return self.declare(name, None);
+                }
+            }
+            Some("quote") if self.is_builtin("quote") => return,
+            Some(name) => {
+                if let Some(arity) = self.resolve(name, &head.cursor, true) {
+                    let actual = arity.positional(args);
+                    if actual < arity.min || arity.max.is_some_and(|max| actual > max) {
+                        self.diagnostics.push(Diagnostic{
+                            kind: DiagnosticKind::WrongArity{name: name.to_string(), min: arity.min, max: arity.max, actual},
+                            cursor: head.cursor.clone(),
+                        });
+                    }
+                }
+                return args.iter().for_each(|a| self.visit(a));
+            }
+            None => {}
+        }
+        children.iter().for_each(|c| self.visit(c));
+    }
+
+    fn visit(&mut self, exp: &PosExpression) {
+        match &exp.exp {
+            SExpression::Symbol(s) => { self.resolve(s, &exp.cursor, false); }
+            SExpression::Number(_) | SExpression::Keyword(_) | SExpression::String(_) => {}
+            SExpression::List(children) => self.visit_list(children),
+            SExpression::Block(children) => self.visit_block(children, true),
+        }
+    }
+}
+
+pub fn check_with_globals(ast: &PosExpression, globals: impl IntoIterator<Item=String>) -> Vec<Diagnostic> {
+    check_with_arities(ast, globals.into_iter().map(|name| (name, None)))
+}
+
+fn check_with_arities(ast: &PosExpression, globals: impl IntoIterator<Item=(String, Option<Arity>)>) -> Vec<Diagnostic> {
+    let mut resolver = Resolver{
+        builtins: globals.into_iter().collect(),
+        frames: vec![],
+        hoisted: HashSet::new(),
+        needs: vec![],
+        diagnostics: vec![],
+    };
+    match &ast.exp {
+        //the source block shares the global scope, just like in the interpreter
+        SExpression::Block(children) => resolver.visit_block(children, false),
+        _ => {
+            resolver.push(false, false);
+            resolver.visit(ast);
+            resolver.pop();
+        }
+    }
+    let mut diagnostics = resolver.diagnostics;
+    diagnostics.sort_by_key(|d| d.cursor.abs_position());
+    diagnostics
+}
+
+pub fn check(ast: &PosExpression) -> Vec<Diagnostic> {
+    let globals = std_lib_functions().into_iter()
+        .map(|b| {
+            let arity = b.arity.map(|(min, max)| Arity{min, max, keywords: b.keywords.clone(), pairs: true});
+            (b.name, arity)
+        })
+        .chain(std::iter::once(("true".to_string(), None)));
+    check_with_arities(ast, globals)
+}
+
+pub fn check_source(source: &str) -> Result<Vec<Diagnostic>, ParserError> {
+    let ast = parse(&mut Lexer::from_text(source).into_iter())?;
+    Ok(check(&ast))
+}
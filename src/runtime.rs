@@ -0,0 +1,153 @@
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::debugger::Debugger;
+use crate::profiler::Profiler;
+use crate::value::ReferenceValue;
+use crate::value::callable::Callable;
+use crate::value::error::{EvalError, Resource};
+use crate::value::list;
+
+pub const MAX_STACK_DEPTH: usize = 420;
+
+//how often the clock is read, Instant::now isn't free
+const DEADLINE_CHECK_INTERVAL: u64 = 1024;
+
+//limits for a single evaluation, None means unlimited
+//this does not bound memory or stack use: only list cells are counted, strings and other values grow
+//unchecked, and call depth is left to the runtime's stack_limit
+#[derive(Debug, Clone, Default)]
+pub struct Budget {
+    //evaluated expressions
+    pub fuel: Option<u64>,
+    pub time: Option<Duration>,
+    //cons cells allocated by lists, not their elements
+    pub list_cells: Option<usize>,
+}
+
+//state shared by every scope of one interpreter
+#[derive(Debug)]
+pub struct Runtime {
+    pub stack_limit: usize,
+    pub budget: Budget,
+    //set from another thread or a signal handler, polled on every step
+    interrupt: Arc<AtomicBool>,
+    steps: Cell<u64>,
+    deadline: Cell<Option<Instant>>,
+    cells_at_start: Cell<usize>,
+    debugger: RefCell<Option<Rc<dyn Debugger>>>,
+    profiler: RefCell<Option<Rc<Profiler>>>,
+    //functions and lambdas whose calls are printed, trace_all prints all of them
+    traced: RefCell<Vec<Rc<ReferenceValue>>>,
+    trace_all: Cell<bool>,
+}
+
+impl Default for Runtime {
+    fn default() -> Self {
+        Runtime::new(MAX_STACK_DEPTH, Budget::default())
+    }
+}
+
+impl Runtime {
+    pub fn new(stack_limit: usize, budget: Budget) -> Runtime {
+        Runtime{
+            stack_limit,
+            budget,
+            interrupt: Arc::new(AtomicBool::new(false)),
+            steps: Cell::new(0),
+            deadline: Cell::new(None),
+            cells_at_start: Cell::new(list::allocated_cells()),
+            debugger: RefCell::new(None),
+            profiler: RefCell::new(None),
+            traced: RefCell::new(Vec::new()),
+            trace_all: Cell::new(false),
+        }
+    }
+
+    pub fn interrupt_handle(&self) -> Arc<AtomicBool> {
+        self.interrupt.clone()
+    }
+
+    //resets the counters, called before every top level evaluation
+    pub fn start(&self) {
+        //an interrupt that arrived while nothing was running is stale
+        self.interrupt.store(false, Ordering::Relaxed);
+        self.steps.set(0);
+        self.deadline.set(self.budget.time.map(|t| Instant::now() + t));
+        self.cells_at_start.set(list::allocated_cells());
+    }
+
+    pub fn set_debugger(&self, debugger: Option<Rc<dyn Debugger>>) {
+        self.debugger.replace(debugger);
+    }
+
+    pub fn debugger(&self) -> Option<Rc<dyn Debugger>> {
+        self.debugger.borrow().clone()
+    }
+
+    pub fn set_profiler(&self, profiler: Option<Rc<Profiler>>) {
+        self.profiler.replace(profiler);
+    }
+
+    pub fn profiler(&self) -> Option<Rc<Profiler>> {
+        self.profiler.borrow().clone()
+    }
+
+    pub fn set_trace_all(&self, trace_all: bool) {
+        self.trace_all.set(trace_all);
+    }
+
+    pub fn trace(&self, function: Rc<ReferenceValue>) {
+        if !self.traced.borrow().iter().any(|t| Rc::ptr_eq(t, &function)) {
+            self.traced.borrow_mut().push(function);
+        }
+    }
+
+    //false if it wasn't traced
+    pub fn untrace(&self, function: &Rc<ReferenceValue>) -> bool {
+        let mut traced = self.traced.borrow_mut();
+        let before = traced.len();
+        traced.retain(|t| !Rc::ptr_eq(t, function));
+        traced.len() != before
+    }
+
+    //builtins are never traced
+    pub fn is_traced(&self, callable: &Callable) -> bool {
+        match callable {
+            Callable::Internal(_) => false,
+            _ if self.trace_all.get() => true,
+            _ => self.traced.borrow().iter()
+                .any(|t| matches!(t.as_ref(), ReferenceValue::CallableValue(c) if std::ptr::eq(c, callable))),
+        }
+    }
+
+    pub fn steps(&self) -> u64 {
+        self.steps.get()
+    }
+
+    //one evaluated expression
+    pub fn step(&self) -> Result<(), EvalError> {
+        if self.interrupt.swap(false, Ordering::Relaxed) {
+            return Err(EvalError::Interrupted);
+        }
+        let steps = self.steps.get() + 1;
+        self.steps.set(steps);
+        if self.budget.fuel.is_some_and(|fuel| steps > fuel) {
+            return Err(EvalError::ResourceExhausted(Resource::Fuel));
+        }
+        if let Some(limit) = self.budget.list_cells {
+            if list::allocated_cells().saturating_sub(self.cells_at_start.get()) > limit {
+                return Err(EvalError::ResourceExhausted(Resource::ListCells));
+            }
+        }
+        if let Some(deadline) = self.deadline.get() {
+            if steps.is_multiple_of(DEADLINE_CHECK_INTERVAL) && Instant::now() >= deadline {
+                return Err(EvalError::ResourceExhausted(Resource::Time));
+            }
+        }
+        Ok(())
+    }
+}
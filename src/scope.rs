@@ -1,10 +1,12 @@
 use std::cell::RefCell;
 use std::collections::HashMap;
 use std::rc::Rc;
+use crate::runtime::Runtime;
 use crate::value::{EvalValue, ReferenceValue};
+use crate::value::builtin::BuiltinFunction;
+use crate::value::callable::Callable;
 use crate::value::error::{ErrorContext, EvalError};
 
-const MAX_STACK_DEPTH: usize = 420;
 
 pub type ScopeRef = Rc<Scope>;
 #[derive(Debug)]
@@ -12,33 +14,62 @@ pub struct Scope {
     pub origin: Option<Rc<ReferenceValue>>, //TODO: it should really only expect function value
     pub depth: usize,
     pub parent: Option<ScopeRef>,
+    pub runtime: Rc<Runtime>,
     entries: RefCell<HashMap<String, EvalValue>>,
-    vararg: Vec<EvalValue>
+    //arguments of the call that created this scope, None for plain blocks
+    vararg: RefCell<Option<Vec<EvalValue>>>,
 }
 
 impl Scope {
     pub fn new() -> Rc<Self> {
-        Rc::new(Scope{origin: None,depth:0,parent: None, entries: Default::default(), vararg: Default::default()})
+        Self::with_runtime(Default::default())
+    }
+
+    pub fn with_runtime(runtime: Rc<Runtime>) -> Rc<Self> {
+        Rc::new(Scope{origin: None,depth:0,parent: None, runtime, entries: Default::default(), vararg: Default::default()})
     }
 
     pub fn enter(self: &Rc<Self>, origin: Option<Rc<ReferenceValue>>) -> Result<Rc<Self>, ErrorContext> {
-        self.enter_with_vararg(vec![], origin)
+        self.check_depth()?;
+        Ok(self.child(None, origin))
+    }
+
+    //arguments of the innermost enclosing call
+    pub fn vararg(&self) -> Option<Vec<EvalValue>> {
+        match (self.vararg.borrow().as_ref(), &self.parent) {
+            (Some(v), _) => Some(v.clone()),
+            (None, Some(parent)) => parent.vararg(),
+            (None, None) => None,
+        }
+    }
+
+    //whether this scope was created for a function or lambda call
+    pub fn is_call(&self) -> bool {
+        self.vararg.borrow().is_some()
     }
 
-    pub fn vararg<'scope>(self: &'scope Rc<Self>) -> &'scope Vec<EvalValue> {
-        &self.vararg
+    pub fn set_vararg(&self, vararg: Vec<EvalValue>) {
+        self.vararg.replace(Some(vararg));
     }
 
     pub fn enter_with_vararg(self: &Rc<Self>, vararg: Vec<EvalValue>, origin: Option<Rc<ReferenceValue>>) -> Result<Rc<Self>, ErrorContext> {
-        if self.depth >= MAX_STACK_DEPTH {
+        self.check_depth()?;
+        Ok(self.child(Some(vararg), origin))
+    }
+
+    fn check_depth(self: &Rc<Self>) -> Result<(), ErrorContext> {
+        if self.depth >= self.runtime.stack_limit {
             Err(EvalError::StackOverflow.trace(self))
-        } else{
-            Ok(
-                Rc::new(Self{origin, depth: self.depth+1, parent: Some(self.clone()), entries: Default::default(), vararg})
-            )
+        } else {
+            Ok(())
         }
     }
 
+    //no stack limit check, used for scopes the host sets up
+    pub fn child(self: &Rc<Self>, vararg: Option<Vec<EvalValue>>, origin: Option<Rc<ReferenceValue>>) -> Rc<Self> {
+        Rc::new(Self{origin, depth: self.depth+1, parent: Some(self.clone()), runtime: self.runtime.clone(), entries: Default::default(), vararg: RefCell::new(vararg)})
+    }
+
     pub fn lookup(&self, identifier: &String) -> Option<EvalValue> {
         if let Some(value) = self.entries.borrow().get(identifier) {
             Some(value.clone())
@@ -49,6 +80,15 @@ impl Scope {
         }
     }
 
+    //entries of this scope only, sorted by name
+    pub fn local_bindings(&self) -> Vec<(String, EvalValue)> {
+        let mut bindings: Vec<(String, EvalValue)> = self.entries.borrow().iter()
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect();
+        bindings.sort_by(|a, b| a.0.cmp(&b.0));
+        bindings
+    }
+
     pub fn clear(&self) -> (){
         let mut map = self.entries.borrow_mut();
         map.clear();
@@ -58,4 +98,9 @@ impl Scope {
         let mut map = self.entries.borrow_mut();
         map.insert(identifier, value);
     }
+
+    pub fn insert_builtin(&self, builtin: BuiltinFunction) {
+        let name = builtin.name.clone();
+        self.insert(name, EvalValue::Reference(ReferenceValue::CallableValue(Callable::Internal(builtin)).to_rc()));
+    }
 }
\ No newline at end of file
@@ -41,22 +41,33 @@ fn lt_eq_callback(scope: &ScopeRef, _ctx: EvalContext, args: BuiltInFunctionArgs
     comparison_reduction(scope, args, |h, v| h<=v)
 }
 
+//equality works on any values, lists and strings compare by content
+fn equality_reduction(scope: &ScopeRef, args: BuiltInFunctionArgs, expect_equal: bool) -> EvalResult {
+    let head = args.try_pos(scope, 0)?.evaluated(scope)?.0;
+    for v in &args.values[1..] {
+        if (head == v.evaluated(scope)?.0) != expect_equal {
+            return Ok((EvalValue::Unit, EvalContext::none()));
+        }
+    }
+    Ok((EvalValue::True, EvalContext::none()))
+}
+
 fn eq_callback(scope: &ScopeRef, _ctx: EvalContext, args: BuiltInFunctionArgs) -> EvalResult {
-    comparison_reduction(scope, args, |h, v| h==v)
+    equality_reduction(scope, args, true)
 }
 
 fn neq_callback(scope: &ScopeRef, _ctx: EvalContext, args: BuiltInFunctionArgs) -> EvalResult {
-    comparison_reduction(scope, args, |h, v| h!=v)
+    equality_reduction(scope, args, false)
 }
 
 pub fn std_comparison() -> Vec<BuiltinFunction> {
     vec![
 
-        func(">", gt_callback),
-        func(">=", gt_eq_callback),
-        func("<", lt_callback),
-        func("<=", lt_eq_callback),
-        func("=", eq_callback),
-        func("!=", neq_callback),
+        func(">", gt_callback).with_doc("(> first rest...)\nTrue when first is greater than each of the rest\nExample: (> 3 2 1) => true"),
+        func(">=", gt_eq_callback).with_doc("(>= first rest...)\nTrue when first is greater than or equal to each of the rest\nExample: (>= 2 2) => true"),
+        func("<", lt_callback).with_doc("(< first rest...)\nTrue when first is less than each of the rest\nExample: (< 1 2 3) => true"),
+        func("<=", lt_eq_callback).with_doc("(<= first rest...)\nTrue when first is less than or equal to each of the rest\nExample: (<= 2 1) => ()"),
+        func("=", eq_callback).with_doc("(= first rest...)\nTrue when every value equals the first, lists and strings compare by content\nExample: (= (list 1 2) (list 1 2)) => true"),
+        func("!=", neq_callback).with_doc("(!= first rest...)\nTrue when no value equals the first\nExample: (!= 1 2) => true"),
     ]
 }
@@ -0,0 +1,193 @@
+use std::iter::Peekable;
+use std::str::Chars;
+
+use crate::builtin;
+use crate::scope::ScopeRef;
+use crate::value::builtin::BuiltinFunction;
+use crate::value::error::{ErrorContext, EvalError};
+use crate::value::numeric::Numeric;
+use crate::value::repr::read_value;
+use crate::value::EvalValue;
+
+//{:[fill]align[0][width][.precision][radix]} with the same meaning as in rust's format!
+
+//rust's format! panics on a larger precision
+const MAX_PRECISION: usize = u16::MAX as usize;
+//keeps a typo in the width from allocating gigabytes
+const MAX_WIDTH: usize = 1 << 20;
+
+#[derive(Default)]
+struct Spec{
+    fill: Option<char>,
+    align: Option<char>,
+    zero: bool,
+    width: usize,
+    precision: Option<usize>,
+    radix: Option<char>,
+}
+
+fn number(chars: &mut Peekable<Chars>) -> Option<usize> {
+    let mut digits = String::new();
+    while let Some(c) = chars.peek().filter(|c| c.is_ascii_digit()) {
+        digits.push(*c);
+        chars.next();
+    }
+    digits.parse().ok()
+}
+
+fn parse_spec(spec: &str) -> Option<Spec> {
+    let mut result = Spec::default();
+    let aligns = ['<', '>', '^'];
+    let mut chars = spec.chars().peekable();
+    let mut lookahead = spec.chars();
+    match (lookahead.next(), lookahead.next()) {
+        (Some(fill), Some(align)) if aligns.contains(&align) => {
+            result.fill = Some(fill);
+            result.align = Some(align);
+            chars.next();
+            chars.next();
+        }
+        (Some(align), _) if aligns.contains(&align) => {
+            result.align = Some(align);
+            chars.next();
+        }
+        _ => {}
+    }
+    if chars.peek() == Some(&'0') {
+        result.zero = true;
+        chars.next();
+    }
+    result.width = number(&mut chars).unwrap_or(0);
+    if chars.peek() == Some(&'.') {
+        chars.next();
+        result.precision = Some(number(&mut chars)?);
+    }
+    if let Some(radix) = chars.next() {
+        if !['x', 'X', 'o', 'b'].contains(&radix) {
+            return None;
+        }
+        result.radix = Some(radix);
+    }
+    chars.next().is_none().then_some(result)
+}
+
+fn render(spec: &Spec, value: &EvalValue) -> Result<String, EvalError> {
+    if spec.precision.is_some_and(|p| p > MAX_PRECISION) {
+        return Err(format_error(format!("precision can be at most {}", MAX_PRECISION)));
+    }
+    if spec.width > MAX_WIDTH {
+        return Err(format_error(format!("width can be at most {}", MAX_WIDTH)));
+    }
+    let body = match (value, spec.radix) {
+        (EvalValue::Numeric(Numeric::Integer(i)), Some(radix)) => {
+            let digits = match radix {
+                'x' => format!("{:x}", i.unsigned_abs()),
+                'X' => format!("{:X}", i.unsigned_abs()),
+                'o' => format!("{:o}", i.unsigned_abs()),
+                _ => format!("{:b}", i.unsigned_abs()),
+            };
+            if *i < 0 { format!("-{}", digits) } else { digits }
+        }
+        (_, Some(_)) => return Err(EvalError::TypeMismatch{expected: "int".to_string(), found: value.type_name()}),
+        (EvalValue::Numeric(Numeric::Integer(i)), None) => match spec.precision {
+            Some(p) => format!("{:.*}", p, f64::from(*i)),
+            None => i.to_string(),
+        },
+        (EvalValue::Numeric(Numeric::Floating(f)), None) => match spec.precision {
+            Some(p) => format!("{:.*}", p, f),
+            None => f.to_string(),
+        },
+        (v, None) => {
+            let text = v.to_string();
+            match spec.precision {
+                Some(p) => text.chars().take(p).collect(),
+                None => text,
+            }
+        }
+    };
+
+    let length = body.chars().count();
+    if length >= spec.width {
+        return Ok(body);
+    }
+    let padding = spec.width - length;
+    //zero padding goes between the sign and the digits
+    if spec.zero && spec.align.is_none() && matches!(value, EvalValue::Numeric(_)) {
+        let (sign, digits) = body.split_at(if body.starts_with('-') { 1 } else { 0 });
+        return Ok(format!("{}{}{}", sign, "0".repeat(padding), digits));
+    }
+    let fill = spec.fill.unwrap_or(' ').to_string();
+    let align = spec.align.unwrap_or(if matches!(value, EvalValue::Numeric(_)) { '>' } else { '<' });
+    Ok(match align {
+        '<' => format!("{}{}", body, fill.repeat(padding)),
+        '>' => format!("{}{}", fill.repeat(padding), body),
+        _ => format!("{}{}{}", fill.repeat(padding / 2), body, fill.repeat(padding - padding / 2)),
+    })
+}
+
+fn format_error(message: impl Into<String>) -> EvalError {
+    EvalError::Other(message.into())
+}
+
+fn format_values(template: &str, values: &[EvalValue]) -> Result<String, EvalError> {
+    let mut output = String::new();
+    let mut values = values.iter();
+    let mut chars = template.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => { chars.next(); output.push('{'); }
+            '}' if chars.peek() == Some(&'}') => { chars.next(); output.push('}'); }
+            '{' => {
+                let mut placeholder = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(c) => placeholder.push(c),
+                        None => return Err(format_error("unterminated placeholder in format string")),
+                    }
+                }
+                let spec = match placeholder.strip_prefix(':') {
+                    Some(spec) => parse_spec(spec),
+                    None if placeholder.is_empty() => Some(Spec::default()),
+                    None => None,
+                }.ok_or_else(|| format_error(format!("invalid placeholder {{{}}}", placeholder)))?;
+                let value = values.next().ok_or_else(|| format_error("not enough arguments for format string"))?;
+                output.push_str(&render(&spec, value)?);
+            }
+            '}' => return Err(format_error("unmatched } in format string")),
+            c => output.push(c),
+        }
+    }
+    if values.next().is_some() {
+        return Err(format_error("too many arguments for format string"));
+    }
+    Ok(output)
+}
+
+/// Fills the {} placeholders of the template, {:>8} {:08} {:.2} {:x} work like in rust
+/// Example: (format "{} is {:.1}" "pi" 3.14159) => "pi is 3.1"
+#[builtin(name = "format", variadic)]
+fn format(scope: &ScopeRef, template: String, values: Vec<EvalValue>) -> Result<String, ErrorContext> {
+    format_values(&template, &values).map_err(|e| e.trace(scope))
+}
+
+/// Readable kisp syntax for the value, read turns it back into an equal value
+/// Example: (repr "hi") => "\"hi\""
+#[builtin(name = "repr")]
+fn repr(value: EvalValue) -> String {
+    value.repr()
+}
+
+/// Parses one form, data literals become values and anything else a quoted expression
+#[builtin(name = "read")]
+fn read(scope: &ScopeRef, source: String) -> Result<EvalValue, ErrorContext> {
+    read_value(&source).map_err(|e| e.trace(scope))
+}
+
+pub fn std_strings() -> Vec<BuiltinFunction> {
+    vec![
+        format_builtin(),
+        repr_builtin(),
+        read_builtin(),
+    ]
+}
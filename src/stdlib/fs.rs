@@ -0,0 +1,103 @@
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::builtin;
+use crate::scope::ScopeRef;
+use crate::value::builtin::BuiltinFunction;
+use crate::value::error::{ErrorContext, EvalError};
+
+fn io_error(scope: &ScopeRef, path: &str, error: std::io::Error) -> ErrorContext {
+    EvalError::Io(format!("{}: {}", path, error)).trace(scope)
+}
+
+/// Reads the whole file into a string
+#[builtin(name = "read-file")]
+fn read_file(scope: &ScopeRef, path: String) -> Result<String, ErrorContext> {
+    fs::read_to_string(&path).map_err(|e| io_error(scope, &path, e))
+}
+
+/// Reads the file as a list of lines without their line endings
+#[builtin(name = "read-lines")]
+fn read_lines(scope: &ScopeRef, path: String) -> Result<Vec<String>, ErrorContext> {
+    let contents = fs::read_to_string(&path).map_err(|e| io_error(scope, &path, e))?;
+    Ok(contents.lines().map(str::to_string).collect())
+}
+
+/// Replaces the contents of the file, creating it if needed
+#[builtin(name = "write-file")]
+fn write_file(scope: &ScopeRef, path: String, contents: String) -> Result<(), ErrorContext> {
+    fs::write(&path, contents).map_err(|e| io_error(scope, &path, e))
+}
+
+/// Adds to the end of the file, creating it if needed
+#[builtin(name = "append-file")]
+fn append_file(scope: &ScopeRef, path: String, contents: String) -> Result<(), ErrorContext> {
+    fs::OpenOptions::new().create(true).append(true).open(&path)
+        .and_then(|mut file| file.write_all(contents.as_bytes()))
+        .map_err(|e| io_error(scope, &path, e))
+}
+
+/// True when something exists at the path, file or directory
+#[builtin(name = "file-exists?")]
+fn file_exists(path: String) -> bool {
+    fs::metadata(path).is_ok()
+}
+
+/// Names of the directory's entries, sorted
+#[builtin(name = "list-dir")]
+fn list_dir(scope: &ScopeRef, path: String) -> Result<Vec<String>, ErrorContext> {
+    let entries = fs::read_dir(&path).map_err(|e| io_error(scope, &path, e))?;
+    let mut names = entries
+        .map(|entry| entry.map(|e| e.file_name().to_string_lossy().into_owned()))
+        .collect::<Result<Vec<String>, _>>()
+        .map_err(|e| io_error(scope, &path, e))?;
+    names.sort();
+    Ok(names)
+}
+
+/// Creates the directory along with any missing parents
+#[builtin(name = "mkdir")]
+fn mkdir(scope: &ScopeRef, path: String) -> Result<(), ErrorContext> {
+    fs::create_dir_all(&path).map_err(|e| io_error(scope, &path, e))
+}
+
+/// Deletes the file
+#[builtin(name = "remove-file")]
+fn remove_file(scope: &ScopeRef, path: String) -> Result<(), ErrorContext> {
+    fs::remove_file(&path).map_err(|e| io_error(scope, &path, e))
+}
+
+/// Joins the parts with the platform's separator
+#[builtin(name = "path-join", variadic)]
+fn path_join(parts: Vec<String>) -> String {
+    parts.iter().collect::<PathBuf>().to_string_lossy().into_owned()
+}
+
+/// Last component of the path, unit if there is none
+#[builtin(name = "basename")]
+fn basename(path: String) -> Option<String> {
+    Path::new(&path).file_name().map(|n| n.to_string_lossy().into_owned())
+}
+
+/// Extension without the dot, unit if there is none
+#[builtin(name = "extension")]
+fn extension(path: String) -> Option<String> {
+    Path::new(&path).extension().map(|e| e.to_string_lossy().into_owned())
+}
+
+pub fn std_fs() -> Vec<BuiltinFunction> {
+    vec![
+        read_file_builtin(),
+        read_lines_builtin(),
+        write_file_builtin(),
+        append_file_builtin(),
+        file_exists_builtin(),
+        list_dir_builtin(),
+        mkdir_builtin(),
+        remove_file_builtin(),
+        path_join_builtin(),
+        basename_builtin(),
+        extension_builtin(),
+    ]
+}
@@ -2,7 +2,7 @@ use std::slice::Iter;
 use crate::expect_ref_type;
 use crate::interpreter::eval_call_with_values;
 use crate::scope::ScopeRef;
-use crate::stdlib::util::func;
+use crate::stdlib::util::{func, keyword_func};
 use crate::value::builtin::{BuiltinFunction, BuiltInFunctionArgs};
 use crate::value::{EvalContext, EvalResult, EvalValue, ReferenceValue};
 use crate::value::callable::Callable;
@@ -125,12 +125,16 @@ fn reduce_callback(scope: &ScopeRef, _ctx: EvalContext, args: BuiltInFunctionArg
 }
 
 fn fold_callback(scope: &ScopeRef, _ctx: EvalContext, args: BuiltInFunctionArgs) -> EvalResult {
-    let (initial, _) = args.try_pos(scope, 0)?.evaluated(scope)?;
+    //(fold init f list) or (fold f list :init init)
+    let (initial, offset) = match args.try_named("init") {
+        Some(init) => (init.evaluated(scope)?.0, 0),
+        None => (args.try_pos(scope, 0)?.evaluated(scope)?.0, 1),
+    };
 
-    let (evaluated_middle, _) = args.try_pos(scope, 1)?.evaluated(scope)?;
+    let (evaluated_middle, _) = args.try_pos(scope, offset)?.evaluated(scope)?;
     let callable = expect_ref_type!(evaluated_middle, ReferenceValue::CallableValue(c) => c, scope)?;
 
-    let (evaluated_right, _) = args.try_pos(scope, 2)?.evaluated(scope)?;
+    let (evaluated_right, _) = args.try_pos(scope, offset + 1)?.evaluated(scope)?;
     let list = expect_ref_type!(evaluated_right, ReferenceValue::List(list) => list, scope)?;
 
     //just makes it simpler to use rust's reduce function
@@ -177,13 +181,13 @@ fn flatten_callback(scope: &ScopeRef, _ctx: EvalContext, args: BuiltInFunctionAr
 
 pub fn std_functional() -> Vec<BuiltinFunction> {
     vec![
-        func("map", map_callback),
-        func("filter", filter_callback),
-        func("enumerate", enumerate_callback),
-        func("zip", zip_callback),
-        func("reduce", reduce_callback),
-        func("fold", fold_callback),
-        func("flatten", flatten_callback),
+        func("map", map_callback).with_doc("(map f list)\nApplies f to every element\nExample: (map (lambda [x] (* x 2)) (list 1 2)) => (list 2 4)"),
+        func("filter", filter_callback).with_doc("(filter f list)\nThe elements for which f isn't unit\nExample: (filter (lambda [x] (> x 1)) (list 1 2 3)) => (list 2 3)"),
+        func("enumerate", enumerate_callback).with_doc("(enumerate list)\nPairs every element with its index\nExample: (enumerate (list :a :b)) => (list (list 0 :a) (list 1 :b))"),
+        func("zip", zip_callback).with_doc("(zip left right)\nPairs up the elements of two lists, stopping at the shorter one\nExample: (zip (list 1 2) (list :a :b)) => (list (list 1 :a) (list 2 :b))"),
+        func("reduce", reduce_callback).with_doc("(reduce f list)\nCombines the elements from the left with f, unit for an empty list\nExample: (reduce + (list 1 2 3)) => 6"),
+        keyword_func("fold", &["init"], fold_callback).with_doc("(fold init f list) or (fold f list :init init)\nCombines the elements from the left with f, starting from init\nExample: (fold 10 + (list 1 2)) => 13"),
+        func("flatten", flatten_callback).with_doc("(flatten list)\nSplices nested lists one level deep\nExample: (flatten (list 1 (list 2 3))) => (list 1 2 3)"),
 
     ]
 }
\ No newline at end of file
@@ -0,0 +1,37 @@
+use std::io::{self, BufRead, Read};
+
+use crate::builtin;
+use crate::scope::ScopeRef;
+use crate::value::builtin::BuiltinFunction;
+use crate::value::error::{ErrorContext, EvalError};
+
+fn io_error(scope: &ScopeRef, error: io::Error) -> ErrorContext {
+    EvalError::Io(format!("stdin: {}", error)).trace(scope)
+}
+
+/// Next line of standard input without its line ending, unit at the end of input
+#[builtin(name = "read-line")]
+fn read_line(scope: &ScopeRef) -> Result<Option<String>, ErrorContext> {
+    let mut line = String::new();
+    let read = io::stdin().lock().read_line(&mut line).map_err(|e| io_error(scope, e))?;
+    if read == 0 {
+        return Ok(None);
+    }
+    let trimmed = line.strip_suffix('\n').unwrap_or(&line);
+    Ok(Some(trimmed.strip_suffix('\r').unwrap_or(trimmed).to_string()))
+}
+
+/// Everything left on standard input
+#[builtin(name = "read-all")]
+fn read_all(scope: &ScopeRef) -> Result<String, ErrorContext> {
+    let mut contents = String::new();
+    io::stdin().lock().read_to_string(&mut contents).map_err(|e| io_error(scope, e))?;
+    Ok(contents)
+}
+
+pub fn std_input() -> Vec<BuiltinFunction> {
+    vec![
+        read_line_builtin(),
+        read_all_builtin(),
+    ]
+}
@@ -0,0 +1,106 @@
+use crate::builtin;
+use crate::json::{self, JsonValue};
+use crate::scope::ScopeRef;
+use crate::stdlib::util::keyword_func;
+use crate::value::builtin::{BuiltinFunction, BuiltInFunctionArgs};
+use crate::value::error::{ErrorContext, EvalError};
+use crate::value::list::List;
+use crate::value::numeric::Numeric;
+use crate::value::{EvalContext, EvalResult, EvalValue, ReferenceValue};
+
+//objects become association lists of (:key value) pairs, false becomes unit,
+//null and {} become keywords of their own so they don't collide with false and []
+const NULL_KEYWORD: &str = "null";
+const EMPTY_OBJECT_KEYWORD: &str = "empty-object";
+
+fn reference(value: ReferenceValue) -> EvalValue {
+    EvalValue::Reference(value.to_rc())
+}
+
+fn from_json(value: JsonValue) -> EvalValue {
+    match value {
+        JsonValue::Null => reference(ReferenceValue::Keyword(NULL_KEYWORD.to_string())),
+        JsonValue::Bool(false) => EvalValue::Unit,
+        JsonValue::Bool(true) => EvalValue::True,
+        JsonValue::Number(n) if n.fract() == 0.0 && n >= f64::from(i32::MIN) && n <= f64::from(i32::MAX) =>
+            EvalValue::Numeric(Numeric::Integer(n as i32)),
+        JsonValue::Number(n) => EvalValue::Numeric(Numeric::Floating(n)),
+        JsonValue::String(s) => reference(ReferenceValue::String(s)),
+        JsonValue::Array(values) => reference(ReferenceValue::List(List::from(values.into_iter().map(from_json).collect()))),
+        JsonValue::Object(entries) if entries.is_empty() => reference(ReferenceValue::Keyword(EMPTY_OBJECT_KEYWORD.to_string())),
+        JsonValue::Object(entries) => reference(ReferenceValue::List(List::from(entries.into_iter()
+            .map(|(k, v)| reference(ReferenceValue::List(List::from(vec![reference(ReferenceValue::Keyword(k)), from_json(v)]))))
+            .collect()))),
+    }
+}
+
+//a non empty list made only of (:key value) pairs
+fn as_object(list: &List) -> Option<Vec<(String, EvalValue)>> {
+    let entries = list.iterator().map(|entry| {
+        let EvalValue::Reference(r) = &entry else { return None };
+        let ReferenceValue::List(pair) = r.as_ref() else { return None };
+        let mut pair = pair.iterator();
+        match (pair.next(), pair.next(), pair.next()) {
+            (Some(key), Some(value), None) => key.keyword().map(|k| (k.to_string(), value)),
+            _ => None,
+        }
+    }).collect::<Option<Vec<_>>>()?;
+    (!entries.is_empty()).then_some(entries)
+}
+
+fn to_json(value: &EvalValue) -> Result<JsonValue, EvalError> {
+    let unsupported = || EvalError::TypeMismatch{expected: "json compatible value".to_string(), found: value.type_name()};
+    match value {
+        EvalValue::Unit => Ok(JsonValue::Bool(false)),
+        EvalValue::True => Ok(JsonValue::Bool(true)),
+        EvalValue::Numeric(Numeric::Integer(i)) => Ok(JsonValue::Number(f64::from(*i))),
+        EvalValue::Numeric(Numeric::Floating(f)) => Ok(JsonValue::Number(*f)),
+        EvalValue::Reference(r) => match r.as_ref() {
+            ReferenceValue::Keyword(k) if k == NULL_KEYWORD => Ok(JsonValue::Null),
+            ReferenceValue::Keyword(k) if k == EMPTY_OBJECT_KEYWORD => Ok(JsonValue::Object(vec![])),
+            ReferenceValue::String(s) | ReferenceValue::Keyword(s) => Ok(JsonValue::String(s.clone())),
+            ReferenceValue::List(list) => match as_object(list) {
+                Some(entries) => entries.into_iter()
+                    .map(|(k, v)| to_json(&v).map(|v| (k, v)))
+                    .collect::<Result<_, _>>()
+                    .map(JsonValue::Object),
+                None => list.iterator().map(|v| to_json(&v)).collect::<Result<_, _>>().map(JsonValue::Array),
+            },
+            _ => Err(unsupported()),
+        },
+    }
+}
+
+/// Reads a json document, objects become lists of (:key value) pairs
+/// false becomes unit, null becomes :null and {} becomes :empty-object
+/// Example: (json-parse "[1, 2]") => (list 1 2)
+#[builtin(name = "json-parse")]
+fn json_parse(scope: &ScopeRef, text: String) -> Result<EvalValue, ErrorContext> {
+    json::parse(&text).map(from_json).map_err(|e| EvalError::Json(e).trace(scope))
+}
+
+//(json-stringify value :pretty true), a number for :pretty sets the indentation
+fn json_stringify_callback(scope: &ScopeRef, _ctx: EvalContext, args: BuiltInFunctionArgs) -> EvalResult {
+    let (value, _) = args.try_pos(scope, 0)?.evaluated(scope)?;
+    let indent = match args.try_named("pretty") {
+        Some(pretty) => match pretty.evaluated(scope)?.0 {
+            EvalValue::Unit => None,
+            EvalValue::Numeric(Numeric::Integer(i)) => Some(i.max(0) as usize),
+            _ => Some(2),
+        },
+        None => None,
+    };
+    let json = to_json(&value).map_err(|e| e.trace(scope))?;
+    let text = match indent {
+        Some(indent) => json.pretty(indent),
+        None => json.to_string(),
+    };
+    Ok((reference(ReferenceValue::String(text)), EvalContext::none()))
+}
+
+pub fn std_json() -> Vec<BuiltinFunction> {
+    vec![
+        json_parse_builtin(),
+        keyword_func("json-stringify", &["pretty"], json_stringify_callback).with_doc("(json-stringify value :pretty indent)\nWrites the value as json, lists of (:key value) pairs become objects\nUnit is written as false, :null as null and :empty-object as {}, other keywords as strings\nExample: (json-stringify (list 1 2)) => \"[1,2]\""),
+    ]
+}
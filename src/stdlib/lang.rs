@@ -1,11 +1,13 @@
-use crate::ast::{PosExpression, SExpression};
+use crate::ast::{parameter_list, PosExpression, SExpression};
+use crate::{builtin, expect_ref_type};
 use crate::value::{EvalContext, EvalResult, EvalValue, ReferenceValue};
-use crate::interpreter::eval_expression;
+use crate::interpreter::{eval_call_with_values, eval_expression};
 use crate::scope::ScopeRef;
 use crate::stacktrace::StackTrace;
 use crate::stdlib::util::func;
 use crate::value::builtin::{BuiltinFunction, BuiltInFunctionArg, BuiltInFunctionArgs};
-use crate::value::callable::{Callable, Function, Lambda};
+use crate::value::callable::{Callable, Function, Lambda, Parameters};
+use crate::value::list::List;
 use crate::value::error::{ErrorContext, EvalError};
 
 
@@ -24,19 +26,19 @@ fn let_callback(scope: &ScopeRef, _ctx: EvalContext, args: BuiltInFunctionArgs)
     Ok((evaluated, EvalContext::none()))
 }
 
-fn get_argument_names(scope: &ScopeRef, possible_args: &BuiltInFunctionArg) -> Result<Vec<String>, ErrorContext> {
-    let block_content = match possible_args.try_expression(scope)? {
-        PosExpression{exp: SExpression::Block(c), ..} => Ok(c),
-        _ => Err(EvalError::InvalidType.trace(scope)),
-    }?;
-    block_content.iter()
-        .map(|exp|
-            match exp {
-                PosExpression{exp: SExpression::Symbol(i), ..} => Ok(i.clone()),
-                _ => Err(EvalError::InvalidType.trace(scope))
-            }
-        )
-        .collect()
+fn get_parameters(scope: &ScopeRef, possible_args: &BuiltInFunctionArg) -> Result<Parameters, ErrorContext> {
+    let specs = parameter_list(possible_args.try_expression(scope)?)
+        .ok_or_else(|| EvalError::InvalidType.trace(scope))?;
+    let mut params = Parameters::default();
+    for spec in specs {
+        match spec.default {
+            _ if spec.rest => params.rest = Some(spec.ident().to_string()),
+            Some(default) if spec.keyword => params.keyword.push((spec.ident().to_string(), default.clone())),
+            Some(default) => params.optional.push((spec.ident().to_string(), default.clone())),
+            None => params.required.push(spec.ident().to_string()),
+        }
+    }
+    Ok(params)
 }
 
 
@@ -46,21 +48,26 @@ fn function_declaration_callback(scope: &ScopeRef, _ctx: EvalContext, args: Buil
         _ => Err(EvalError::InvalidType.trace(scope)),
     }?;
 
-    let arg_names: Vec<String> = get_argument_names(scope, args.try_pos(scope, 1)?)?;
-    let body = args.try_pos(scope, 2)?.try_expression(scope)?;
+    let arg_names = get_parameters(scope, args.try_pos(scope, 1)?)?;
+    //(fn name [params] "docstring" body), a lone string is still the body
+    let (doc, body_pos) = match (args.try_pos(scope, 2)?.try_expression(scope)?, args.values.len()) {
+        (PosExpression{exp: SExpression::String(doc), ..}, 4) => (Some(doc.clone()), 3),
+        _ => (None, 2),
+    };
+    let body = args.try_pos(scope, body_pos)?.try_expression(scope)?;
     let function = Function::from(
         scope.clone(),
         name.clone(),
         arg_names,
         body
-    );
+    ).with_doc(doc);
     let function_value =  EvalValue::Reference(ReferenceValue::CallableValue(Callable::Function(function)).to_rc());
     scope.insert(name, function_value.clone());
     Ok((function_value, EvalContext::none()))
 }
 
 fn lambda_callback(scope: &ScopeRef, _ctx: EvalContext, args: BuiltInFunctionArgs) -> EvalResult {
-    let arguments: Vec<String> =  get_argument_names(scope, args.try_pos(scope, 0)?)?;
+    let arguments = get_parameters(scope, args.try_pos(scope, 0)?)?;
     let body=  args.try_pos(scope, 1)?.try_expression(scope)?.clone();
     let lambda = Lambda{
         in_scope: scope.clone(),
@@ -111,15 +118,81 @@ fn eval_callback(scope: &ScopeRef, _ctx: EvalContext, args: BuiltInFunctionArgs)
     }
 }
 
+//arguments of the enclosing function call, regardless of how they were bound
+fn args_callback(scope: &ScopeRef, _ctx: EvalContext, _args: BuiltInFunctionArgs) -> EvalResult {
+    let values = scope.vararg().unwrap_or_default();
+    Ok((EvalValue::Reference(ReferenceValue::List(List::from(values)).to_rc()), EvalContext::none()))
+}
+
+//(invoke object :method args...), the object is passed on as the method's first argument
+fn invoke_callback(scope: &ScopeRef, _ctx: EvalContext, args: BuiltInFunctionArgs) -> EvalResult {
+    let (target, _) = args.try_pos(scope, 0)?.evaluated(scope)?;
+    let kind = expect_ref_type!(target, ReferenceValue::Native(n) => n.kind.clone(), scope)?;
+    let (method_value, _) = args.try_pos(scope, 1)?.evaluated(scope)?;
+    let method_name = match method_value.keyword() {
+        Some(k) => k.to_string(),
+        None => expect_ref_type!(method_value, ReferenceValue::String(s) => s.clone(), scope)?,
+    };
+    let method = kind.method(&method_name).ok_or_else(||
+        EvalError::UnknownMethod{type_name: kind.name.clone(), method: method_name.clone()}.trace(scope)
+    )?;
+    let mut values = vec![target.clone()];
+    for arg in &args.values[2..] {
+        values.push(arg.evaluated(scope)?.0);
+    }
+    (method.callback)(scope, EvalContext::none(), BuiltInFunctionArgs::with_keywords(scope, values, &method.keywords)?)
+}
+
+//(try body [handler]), on error the handler gets the message, without one the result is unit
+fn try_callback(scope: &ScopeRef, _ctx: EvalContext, args: BuiltInFunctionArgs) -> EvalResult {
+    let body = args.try_pos(scope, 0)?.try_expression(scope)?;
+    let error = match eval_expression(EvalContext::none(), scope, body) {
+        Ok(result) => return Ok(result),
+        Err(e) if !e.error().is_catchable() => return Err(e),
+        Err(e) => e,
+    };
+    let Ok(handler) = args.try_pos(scope, 1) else {
+        return Ok((EvalValue::Unit, EvalContext::none()));
+    };
+    let (handler, _) = handler.evaluated(scope)?;
+    let callable = expect_ref_type!(handler, ReferenceValue::CallableValue(c) => c, scope)?;
+    let message = EvalValue::Reference(ReferenceValue::String(error.error().to_string()).to_rc());
+    eval_call_with_values(EvalContext::none(), scope, callable, vec![message], None)
+}
+
+//a callable, or the name of one as a string or keyword
+pub fn documentation(scope: &ScopeRef, target: &EvalValue) -> Result<String, ErrorContext> {
+    let name = match target {
+        EvalValue::Reference(r) => match r.as_ref() {
+            ReferenceValue::CallableValue(c) => return Ok(c.documentation()),
+            ReferenceValue::String(s) | ReferenceValue::Keyword(s) => s,
+            _ => return Err(EvalError::TypeMismatch{expected: "function or name".to_string(), found: target.type_name()}.trace(scope)),
+        },
+        _ => return Err(EvalError::TypeMismatch{expected: "function or name".to_string(), found: target.type_name()}.trace(scope)),
+    };
+    let value = scope.lookup(name).ok_or_else(|| EvalError::UnknownSymbol(name.clone()).trace(scope))?;
+    let callable = expect_ref_type!(value, ReferenceValue::CallableValue(c) => c, scope)?;
+    Ok(callable.documentation())
+}
+
+/// Signature and documentation of a function, given the function itself or its name
+/// Example: (doc "args") => "(args)\nList of the arguments the enclosing function was called with"
+#[builtin(name = "doc")]
+fn doc(scope: &ScopeRef, target: EvalValue) -> Result<String, ErrorContext> {
+    documentation(scope, &target)
+}
 
 pub fn std_lang() -> Vec<BuiltinFunction> {
     vec![
-        func("let", let_callback),
-        func("fn", function_declaration_callback),
-        func("lambda", lambda_callback),
-        func("if", if_callback),
-        func("quote", quote_callback),
-        func("eval", eval_callback),
-
+        func("let", let_callback).with_doc("(let name value)\nBinds the value to the name in the current scope\nExample: (let x 5) => 5"),
+        func("fn", function_declaration_callback).with_doc("(fn name [params] [docstring] body)\nDefines a named function, a string before the body documents it\nExample: (fn square [x] \"Multiplies x by itself\" (* x x))"),
+        func("lambda", lambda_callback).with_doc("(lambda [params] body)\nAn anonymous function closing over the current scope\nExample: ((lambda [x] (+ x 1)) 1) => 2"),
+        func("if", if_callback).with_doc("(if condition then [else])\nEvaluates then unless the condition is unit, else otherwise\nExample: (if (> 2 1) \"yes\" \"no\") => \"yes\""),
+        func("quote", quote_callback).with_doc("(quote expression)\nThe expression itself, unevaluated\nExample: (quote (+ 1 2)) => (quote (+ 1 2))"),
+        func("eval", eval_callback).with_doc("(eval value)\nEvaluates a quoted expression, other values are returned as they are\nExample: (eval (quote (+ 1 2))) => 3"),
+        func("args", args_callback).with_doc("(args)\nList of the arguments the enclosing function was called with"),
+        func("invoke", invoke_callback).with_doc("(invoke object :method args...)\nCalls a method of a host object, the object is passed as the first argument"),
+        func("try", try_callback).with_doc("(try body [handler])\nEvaluates the body, on error calls the handler with the message or returns unit\nExample: (try (car 1) (lambda [e] \"failed\")) => \"failed\""),
+        doc_builtin(),
     ]
 }
@@ -1,18 +1,18 @@
-use crate::{expect_copy_type, expect_ref_type};
+use crate::{builtin, expect_copy_type, expect_ref_type};
 use crate::value::{EvalContext, EvalResult, EvalValue, ReferenceValue};
-use crate::interpreter::eval_call_with_values;
 use crate::value::list::List;
 use crate::value::numeric::Numeric;
 use crate::scope::ScopeRef;
-use crate::stdlib::util::func;
+use crate::stdlib::util::keyword_func;
 use crate::value::builtin::{BuiltinFunction, BuiltInFunctionArgs};
 use crate::value::error::EvalError;
 
 
-fn list_callback(scope: &ScopeRef, _ctx: EvalContext, args: BuiltInFunctionArgs) -> EvalResult {
-    let vals: Vec<EvalValue> = args.eval_all(scope)?;
-    let list = List::from(vals.clone());
-    Ok((EvalValue::Reference(ReferenceValue::List(list).to_rc()), EvalContext::none()))
+/// Builds a list out of its arguments
+/// Example: (list 1 2 3) => (list 1 2 3)
+#[builtin(name = "list", variadic)]
+fn list(values: Vec<EvalValue>) -> List {
+    List::from(values)
 }
 
 fn wrap_opt_to_unit(v: Option<EvalValue>) -> EvalValue {
@@ -22,32 +22,38 @@ fn wrap_opt_to_unit(v: Option<EvalValue>) -> EvalValue {
     }
 }
 fn nth_callback(scope: &ScopeRef, _ctx: EvalContext, args: BuiltInFunctionArgs) -> EvalResult {
-    let (list_value, _) = args.try_pos(scope, 1)?.evaluated(scope)?;
+    //(nth index list) or (nth list :index index)
+    let (index, list_pos) = match args.try_named("index") {
+        Some(index) => (index, 0),
+        None => (args.try_pos(scope, 0)?, 1),
+    };
+    let (list_value, _) = args.try_pos(scope, list_pos)?.evaluated(scope)?;
     let list = expect_ref_type!(list_value, ReferenceValue::List(l) => l, scope)?;
-    let (arg_value, _) = args.try_pos(scope, 0)?.evaluated(scope)?;
+    let (arg_value, _) = index.evaluated(scope)?;
     let pos = expect_copy_type!(arg_value, EvalValue::Numeric(Numeric::Integer(pos)) => pos as usize, scope)?;
     Ok((wrap_opt_to_unit(list.get(pos)), EvalContext::none()))
 }
 
 
-fn car_callback(scope: &ScopeRef, _ctx: EvalContext, args:  BuiltInFunctionArgs) -> EvalResult {
-    let (arg_value, _ )  = args.try_pos(scope, 0)?.evaluated(scope)?;
-    let list = expect_ref_type!(arg_value, ReferenceValue::List(v) => v, scope)?;
-    Ok((wrap_opt_to_unit(list.head()), EvalContext::none()))
+/// First element, unit for an empty list
+/// Example: (car (list 1 2)) => 1
+#[builtin(name = "car")]
+fn car(list: List) -> Option<EvalValue> {
+    list.head()
 }
 
-fn cdr_callback(scope: &ScopeRef, _ctx: EvalContext, args:  BuiltInFunctionArgs) -> EvalResult {
-    let (arg_value, _ )  = args.try_pos(scope, 0)?.evaluated(scope)?;
-    let list = expect_ref_type!(arg_value, ReferenceValue::List(l) => l, scope)?;
-    Ok((EvalValue::Reference(ReferenceValue::List(list.tail()).to_rc()), EvalContext::none()))
+/// Everything but the first element
+/// Example: (cdr (list 1 2)) => (list 2)
+#[builtin(name = "cdr")]
+fn cdr(list: List) -> List {
+    list.tail()
 }
 
-
-fn cons_callback(scope: &ScopeRef, _ctx: EvalContext, args:  BuiltInFunctionArgs) -> EvalResult {
-    let (arg_value, _ )  = args.try_pos(scope, 1)?.evaluated(scope)?;
-    let list = expect_ref_type!(arg_value, ReferenceValue::List(l) => l, scope)?;
-    let (con_value, _ )  = args.try_pos(scope, 0)?.evaluated(scope)?;
-    Ok((EvalValue::Reference(ReferenceValue::List(list.prepended(con_value)).to_rc()), EvalContext::none()))
+/// Prepends a value to the list
+/// Example: (cons 0 (list 1)) => (list 0 1)
+#[builtin(name = "cons")]
+fn cons(value: EvalValue, list: List) -> List {
+    list.prepended(value)
 }
 
 
@@ -55,10 +61,10 @@ fn cons_callback(scope: &ScopeRef, _ctx: EvalContext, args:  BuiltInFunctionArgs
 
 pub fn std_lists() -> Vec<BuiltinFunction> {
     vec![
-        func("list", list_callback),
-        func("car", car_callback),
-        func("cdr", cdr_callback),
-        func("cons", cons_callback),
-        func("nth", nth_callback),
+        list_builtin(),
+        car_builtin(),
+        cdr_builtin(),
+        cons_builtin(),
+        keyword_func("nth", &["index"], nth_callback).with_doc("(nth index list) or (nth list :index index)\nElement at the zero based index, unit past the end\nExample: (nth 1 (list :a :b)) => :b").with_arity(2, Some(2)),
     ]
 }
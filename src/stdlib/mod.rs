@@ -1,9 +1,15 @@
 use crate::stdlib::arithmetic::std_arithmetic;
 use crate::stdlib::comparison::std_comparison;
+use crate::stdlib::format::std_strings;
+use crate::stdlib::fs::std_fs;
+use crate::stdlib::input::std_input;
+use crate::stdlib::json::std_json;
 use crate::stdlib::functional::std_functional;
 use crate::stdlib::lang::std_lang;
 use crate::stdlib::lists::std_lists;
 use crate::stdlib::output::std_output;
+use crate::stdlib::process::std_process;
+use crate::stdlib::time::std_time;
 use crate::stdlib::types::std_types;
 use crate::value::builtin::BuiltinFunction;
 
@@ -15,17 +21,118 @@ mod lang;
 mod lists;
 mod types;
 mod functional;
+mod fs;
+mod format;
+mod input;
+mod json;
+mod process;
+mod time;
 
 
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StdModule{
+    Lang,
+    Arithmetic,
+    Comparison,
+    Output,
+    Lists,
+    Types,
+    Functional,
+    Strings,
+    Json,
+    Input,
+    Filesystem,
+    Process,
+    Time,
+}
+
+impl StdModule{
+    //no side effects outside the interpreter, Budget says what a script can still use up
+    pub const PURE: [StdModule; 8] = [
+        StdModule::Lang,
+        StdModule::Arithmetic,
+        StdModule::Comparison,
+        StdModule::Lists,
+        StdModule::Types,
+        StdModule::Functional,
+        StdModule::Strings,
+        StdModule::Json,
+    ];
+
+    //pure core plus printing, what an interpreter gets unless told otherwise
+    pub const DEFAULT: [StdModule; 9] = [
+        StdModule::Lang,
+        StdModule::Arithmetic,
+        StdModule::Comparison,
+        StdModule::Output,
+        StdModule::Lists,
+        StdModule::Types,
+        StdModule::Functional,
+        StdModule::Strings,
+        StdModule::Json,
+    ];
+
+    pub const ALL: [StdModule; 13] = [
+        StdModule::Lang,
+        StdModule::Arithmetic,
+        StdModule::Comparison,
+        StdModule::Output,
+        StdModule::Lists,
+        StdModule::Types,
+        StdModule::Functional,
+        StdModule::Strings,
+        StdModule::Json,
+        StdModule::Input,
+        StdModule::Filesystem,
+        StdModule::Process,
+        StdModule::Time,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            StdModule::Lang => "lang",
+            StdModule::Arithmetic => "arithmetic",
+            StdModule::Comparison => "comparison",
+            StdModule::Output => "output",
+            StdModule::Lists => "lists",
+            StdModule::Types => "types",
+            StdModule::Functional => "functional",
+            StdModule::Strings => "strings",
+            StdModule::Json => "json",
+            StdModule::Input => "input",
+            StdModule::Filesystem => "fs",
+            StdModule::Process => "process",
+            StdModule::Time => "time",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<StdModule> {
+        StdModule::ALL.iter().find(|m| m.name() == name).copied()
+    }
+
+    pub fn functions(&self) -> Vec<BuiltinFunction> {
+        match self {
+            StdModule::Lang => std_lang(),
+            StdModule::Arithmetic => std_arithmetic(),
+            StdModule::Comparison => std_comparison(),
+            StdModule::Output => std_output(),
+            StdModule::Lists => std_lists(),
+            StdModule::Types => std_types(),
+            StdModule::Functional => std_functional(),
+            StdModule::Strings => std_strings(),
+            StdModule::Json => std_json(),
+            StdModule::Input => std_input(),
+            StdModule::Filesystem => std_fs(),
+            StdModule::Process => std_process(),
+            StdModule::Time => std_time(),
+        }
+    }
+}
+
 pub fn std_lib_functions() -> Vec<BuiltinFunction> {
-    vec![
-        std_lang(),
-        std_arithmetic(),
-        std_comparison(),
-        std_output(),
-        std_lists(),
-        std_types(),
-        std_functional(),
-
-    ].into_iter().flatten().collect()
+    StdModule::ALL.iter().flat_map(|m| m.functions()).collect()
+}
+
+pub fn modules_functions(modules: &[StdModule]) -> Vec<BuiltinFunction> {
+    modules.iter().flat_map(|m| m.functions()).collect()
 }
@@ -1,21 +1,78 @@
-use crate::value::{EvalContext, EvalResult, EvalValue, ReferenceValue};
+use std::io::{self, Write};
+use std::rc::Rc;
+
+use crate::builtin;
 use crate::scope::ScopeRef;
-use crate::stdlib::util::{func};
-use crate::value::builtin::{BuiltinFunction, BuiltInFunctionArgs};
+use crate::stdlib::lang::documentation;
+use crate::value::{EvalValue, ReferenceValue};
+use crate::value::builtin::BuiltinFunction;
+use crate::value::callable::Callable;
+use crate::value::error::{ErrorContext, EvalError};
 
-fn print_callback(scope: &ScopeRef, _ctx: EvalContext, args: BuiltInFunctionArgs) -> EvalResult {
-    let vals = args.eval_all(scope)?;
-    let string = vals.iter()
+fn join(values: &[EvalValue]) -> String {
+    values.iter()
         .map(|v|v.to_string())
         .collect::<Vec<String>>()
-        .join(" ");
-    println!("{}", string);
-    Ok((EvalValue::Unit, EvalContext::none()))
+        .join(" ")
+}
+
+/// Prints the arguments separated by spaces
+#[builtin(name = "print", variadic)]
+fn print(values: Vec<EvalValue>) {
+    println!("{}", join(&values));
+}
+
+/// Like print, without the trailing newline
+#[builtin(name = "write", variadic)]
+fn write(values: Vec<EvalValue>) {
+    let mut stdout = io::stdout().lock();
+    let _ = stdout.write_all(join(&values).as_bytes());
+    let _ = stdout.flush();
+}
+
+/// Like print, to standard error
+#[builtin(name = "eprint", variadic)]
+fn eprint(values: Vec<EvalValue>) {
+    eprintln!("{}", join(&values));
+}
+
+/// Prints the signature and documentation of a function, given the function itself or its name
+/// Example: (help map)
+#[builtin(name = "help")]
+fn help(scope: &ScopeRef, target: EvalValue) -> Result<(), ErrorContext> {
+    println!("{}", documentation(scope, &target)?);
+    Ok(())
+}
+
+//builtins have no body to trace, only functions and lambdas qualify
+fn traceable(scope: &ScopeRef, value: EvalValue) -> Result<Rc<ReferenceValue>, ErrorContext> {
+    match &value {
+        EvalValue::Reference(r) if matches!(r.as_ref(), ReferenceValue::CallableValue(Callable::Function(_) | Callable::Lambda(_))) => Ok(r.clone()),
+        _ => Err(EvalError::TypeMismatch{expected: "function or lambda".to_string(), found: value.type_name()}.trace(scope)),
+    }
+}
+
+/// Prints every call of the function to standard error with its arguments and its result
+/// Example: (trace square)
+#[builtin(name = "trace")]
+fn trace(scope: &ScopeRef, function: EvalValue) -> Result<(), ErrorContext> {
+    scope.runtime.trace(traceable(scope, function)?);
+    Ok(())
+}
+
+/// Stops tracing the function, true if it was traced
+#[builtin(name = "untrace")]
+fn untrace(scope: &ScopeRef, function: EvalValue) -> Result<bool, ErrorContext> {
+    Ok(scope.runtime.untrace(&traceable(scope, function)?))
 }
 
 pub fn std_output() -> Vec<BuiltinFunction> {
     vec![
-
-        func("print", print_callback)
+        print_builtin(),
+        write_builtin(),
+        eprint_builtin(),
+        help_builtin(),
+        trace_builtin(),
+        untrace_builtin(),
     ]
 }
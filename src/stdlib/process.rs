@@ -0,0 +1,25 @@
+use std::env;
+
+use crate::builtin;
+use crate::scope::ScopeRef;
+use crate::value::builtin::BuiltinFunction;
+use crate::value::error::{ErrorContext, EvalError};
+
+/// Value of an environment variable, unit if it isn't set
+#[builtin(name = "getenv")]
+fn getenv(name: String) -> Option<String> {
+    env::var(name).ok()
+}
+
+/// Stops the script, the host ends the process with the given status code
+#[builtin(name = "exit")]
+fn exit(scope: &ScopeRef, code: Option<i32>) -> Result<(), ErrorContext> {
+    Err(EvalError::Exit(code.unwrap_or(0)).trace(scope))
+}
+
+pub fn std_process() -> Vec<BuiltinFunction> {
+    vec![
+        getenv_builtin(),
+        exit_builtin(),
+    ]
+}
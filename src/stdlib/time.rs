@@ -0,0 +1,29 @@
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::builtin;
+use crate::scope::ScopeRef;
+use crate::value::builtin::BuiltinFunction;
+use crate::value::error::{ErrorContext, EvalError};
+
+/// Seconds since the unix epoch
+#[builtin(name = "now")]
+fn now() -> f64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs_f64()).unwrap_or(0.0)
+}
+
+/// Blocks for the given number of milliseconds
+#[builtin(name = "sleep")]
+fn sleep(scope: &ScopeRef, millis: f64) -> Result<(), ErrorContext> {
+    let duration = Duration::try_from_secs_f64(millis.max(0.0) / 1000.0)
+        .map_err(|_| EvalError::Other(format!("can't sleep for {} milliseconds", millis)).trace(scope))?;
+    thread::sleep(duration);
+    Ok(())
+}
+
+pub fn std_time() -> Vec<BuiltinFunction> {
+    vec![
+        now_builtin(),
+        sleep_builtin(),
+    ]
+}
@@ -1,102 +1,141 @@
-use crate::expect_copy_type;
-use crate::scope::ScopeRef;
-use crate::stdlib::util::func;
-use crate::value::builtin::{BuiltinFunction, BuiltInFunctionArgs};
-use crate::value::{EvalContext, EvalResult, EvalValue, ReferenceValue};
+use crate::builtin;
+use crate::value::builtin::BuiltinFunction;
+use crate::value::{EvalValue, ReferenceValue};
 use crate::value::callable::Callable;
-use crate::value::error::EvalError;
 use crate::value::numeric::Numeric;
 
 
-fn num_cast_callback(scope: &ScopeRef, _ctx: EvalContext, args: BuiltInFunctionArgs, cast: fn(Numeric) -> Numeric) -> EvalResult {
-    let arg = args.try_pos(scope, 0)?.evaluated(scope)?.0;
-    let v = expect_copy_type!(arg, EvalValue::Numeric(n) => n, scope)?;
-    let casted = cast(v);
-    Ok((EvalValue::Numeric(casted), EvalContext::none()))
+/// Truncates to an integer
+/// Example: (int 2.7) => 2
+#[builtin(name = "int")]
+fn int(value: Numeric) -> Numeric {
+    value.cast_int()
 }
 
-fn int_callback(scope: &ScopeRef, _ctx: EvalContext, args: BuiltInFunctionArgs) -> EvalResult {
-    num_cast_callback(scope, _ctx, args, |n| n.cast_int())
+/// Converts to a floating point number
+#[builtin(name = "float")]
+fn float(value: Numeric) -> Numeric {
+    value.cast_fp()
 }
 
-fn float_callback(scope: &ScopeRef, _ctx: EvalContext, args: BuiltInFunctionArgs) -> EvalResult {
-    num_cast_callback(scope, _ctx, args, |n| n.cast_fp())
+fn is_reference(value: &EvalValue, check: fn(&ReferenceValue) -> bool) -> bool {
+    match value {
+        EvalValue::Reference(r) => check(r.as_ref()),
+        _ => false,
+    }
 }
 
-fn type_check_callback(scope: &ScopeRef, _ctx: EvalContext, args: BuiltInFunctionArgs, check: impl Fn(EvalValue) -> bool) -> EvalResult {
-    let arg = args.try_pos(scope, 0)?.evaluated(scope)?.0;
-    let check_ret = check(arg);
-    let ret = if check_ret{
-        EvalValue::True
-    }else{
-        EvalValue::Unit
-    };
-    Ok((ret, EvalContext::none()))
+/// True for unit, the empty list
+#[builtin(name = "is-unit?")]
+fn is_unit(value: EvalValue) -> bool {
+    matches!(value, EvalValue::Unit)
 }
 
-fn ref_type_check_callback(scope: &ScopeRef, _ctx: EvalContext, args: BuiltInFunctionArgs, check: fn(&ReferenceValue) -> bool) -> EvalResult {
-    type_check_callback(scope, _ctx, args, |t|
-        match &t {
-            EvalValue::Reference(r) => check(r.as_ref()),
-            _ => false,
-        }
-    )
+/// True for integers and floats
+#[builtin(name = "is-numeric?")]
+fn is_numeric(value: EvalValue) -> bool {
+    matches!(value, EvalValue::Numeric(_))
+}
+
+/// True for integers
+#[builtin(name = "is-int?")]
+fn is_int(value: EvalValue) -> bool {
+    matches!(value, EvalValue::Numeric(Numeric::Integer(_)))
 }
 
+/// True for floating point numbers
+#[builtin(name = "is-float?")]
+fn is_float(value: EvalValue) -> bool {
+    matches!(value, EvalValue::Numeric(Numeric::Floating(_)))
+}
 
-fn is_unit_callback(scope: &ScopeRef, _ctx: EvalContext, args: BuiltInFunctionArgs) -> EvalResult {
-    type_check_callback(scope, _ctx, args, |t| matches!(t, EvalValue::Unit))
+/// True for lists, including the empty (list), unit is not one
+#[builtin(name = "is-list?")]
+fn is_list(value: EvalValue) -> bool {
+    is_reference(&value, |r| matches!(r, ReferenceValue::List(_)))
 }
 
-fn is_numeric_callback(scope: &ScopeRef, _ctx: EvalContext, args: BuiltInFunctionArgs) -> EvalResult {
-    type_check_callback(scope, _ctx, args, |t| matches!(t, EvalValue::Numeric(_)))
+/// True for strings
+#[builtin(name = "is-string?")]
+fn is_string(value: EvalValue) -> bool {
+    is_reference(&value, |r| matches!(r, ReferenceValue::String(_)))
 }
 
-fn is_int_callback(scope: &ScopeRef, _ctx: EvalContext, args: BuiltInFunctionArgs) -> EvalResult {
-    type_check_callback(scope, _ctx, args, |t| matches!(t, EvalValue::Numeric(Numeric::Integer(_))))
+/// True for keywords like :name
+#[builtin(name = "is-keyword?")]
+fn is_keyword(value: EvalValue) -> bool {
+    is_reference(&value, |r| matches!(r, ReferenceValue::Keyword(_)))
 }
 
-fn is_float_callback(scope: &ScopeRef, _ctx: EvalContext, args: BuiltInFunctionArgs) -> EvalResult {
-    type_check_callback(scope, _ctx, args, |t| matches!(t, EvalValue::Numeric(Numeric::Floating(_))))
+/// True for host objects, optionally only those of the given type
+#[builtin(name = "is-native?")]
+fn is_native(value: EvalValue, type_name: Option<String>) -> bool {
+    match &value {
+        EvalValue::Reference(r) => match r.as_ref() {
+            ReferenceValue::Native(n) => type_name.is_none_or(|t| t == n.type_name()),
+            _ => false,
+        },
+        _ => false,
+    }
 }
 
-fn is_list_callback(scope: &ScopeRef, _ctx: EvalContext, args: BuiltInFunctionArgs) -> EvalResult {
-    ref_type_check_callback(scope, _ctx, args, |r| matches!(r, ReferenceValue::List(_)))
+/// Name of the value's type, host objects report their own
+#[builtin(name = "type-name")]
+fn type_name(value: EvalValue) -> String {
+    match &value {
+        EvalValue::Reference(r) => match r.as_ref() {
+            ReferenceValue::Native(n) => n.type_name().to_string(),
+            _ => value.type_name().to_string(),
+        },
+        _ => value.type_name().to_string(),
+    }
 }
 
-fn is_callable_callback(scope: &ScopeRef, _ctx: EvalContext, args: BuiltInFunctionArgs) -> EvalResult {
-    ref_type_check_callback(scope, _ctx, args, |r| matches!(r, ReferenceValue::CallableValue(_)))
+/// True for anything that can be called
+#[builtin(name = "is-callable?")]
+fn is_callable(value: EvalValue) -> bool {
+    is_reference(&value, |r| matches!(r, ReferenceValue::CallableValue(_)))
 }
 
-fn is_builtin_callback(scope: &ScopeRef, _ctx: EvalContext, args: BuiltInFunctionArgs) -> EvalResult {
-    ref_type_check_callback(scope, _ctx, args, |r| matches!(r, ReferenceValue::CallableValue(Callable::Internal(_))))
+/// True for functions provided by the host
+#[builtin(name = "is-builtin?")]
+fn is_builtin(value: EvalValue) -> bool {
+    is_reference(&value, |r| matches!(r, ReferenceValue::CallableValue(Callable::Internal(_))))
 }
 
-fn is_lambda_callback(scope: &ScopeRef, _ctx: EvalContext, args: BuiltInFunctionArgs) -> EvalResult {
-    ref_type_check_callback(scope, _ctx, args, |r| matches!(r, ReferenceValue::CallableValue(Callable::Lambda(_))))
+/// True for anonymous functions
+#[builtin(name = "is-lambda?")]
+fn is_lambda(value: EvalValue) -> bool {
+    is_reference(&value, |r| matches!(r, ReferenceValue::CallableValue(Callable::Lambda(_))))
 }
 
-fn is_function_callback(scope: &ScopeRef, _ctx: EvalContext, args: BuiltInFunctionArgs) -> EvalResult {
-    ref_type_check_callback(scope, _ctx, args, |r| matches!(r, ReferenceValue::CallableValue(Callable::Function(_))))
+/// True for functions defined with fn
+#[builtin(name = "is-function?")]
+fn is_function(value: EvalValue) -> bool {
+    is_reference(&value, |r| matches!(r, ReferenceValue::CallableValue(Callable::Function(_))))
 }
 
 
 
 pub fn std_types() -> Vec<BuiltinFunction> {
     vec![
-        func("int", int_callback),
-        func("float", int_callback),
-
-        func("is-unit?", is_unit_callback),
-        func("is-unit?", is_unit_callback),
-        func("is-numeric?", is_numeric_callback),
-        func("is-int?", is_int_callback),
-        func("is-float?", is_float_callback),
-        func("is-unit?", is_unit_callback),
-        func("is_list?", is_list_callback),
-        func("is-callable?", is_callable_callback),
-        func("is-builtin?", is_builtin_callback),
-        func("is-lambda?", is_lambda_callback),
-        func("is-function?", is_function_callback),
+        int_builtin(),
+        float_builtin(),
+
+        is_unit_builtin(),
+        is_numeric_builtin(),
+        is_int_builtin(),
+        is_float_builtin(),
+        is_list_builtin(),
+        //the name it was registered under before, kept for old scripts
+        BuiltinFunction{name: "is_list?".to_string(), ..is_list_builtin()}.with_doc("(is_list? value)\nOld name of is-list?"),
+        is_string_builtin(),
+        is_keyword_builtin(),
+        is_native_builtin(),
+        type_name_builtin(),
+        is_callable_builtin(),
+        is_builtin_builtin(),
+        is_lambda_builtin(),
+        is_function_builtin(),
     ]
-}
\ No newline at end of file
+}
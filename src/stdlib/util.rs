@@ -1,8 +1,15 @@
-use crate::value::builtin::BuiltinFunction;
-use crate::value::builtin::InternalCallback;
-use crate::value::{ReferenceValue, EvalValue};
-pub fn func(name: &'static str, callback: InternalCallback) -> BuiltinFunction{
-    BuiltinFunction{ callback, name }
+use crate::scope::ScopeRef;
+use crate::value::builtin::{BuiltinFunction, BuiltInFunctionArgs};
+use crate::value::{ReferenceValue, EvalValue, EvalContext, EvalResult};
+
+type Callback = fn(&ScopeRef, EvalContext, BuiltInFunctionArgs) -> EvalResult;
+
+pub fn func(name: &'static str, callback: Callback) -> BuiltinFunction{
+    BuiltinFunction::new(name, callback)
+}
+
+pub fn keyword_func(name: &'static str, keywords: &'static [&'static str], callback: Callback) -> BuiltinFunction{
+    BuiltinFunction::new(name, callback).with_keywords(keywords.iter().copied())
 }
 
 #[macro_export]
@@ -1,6 +1,11 @@
-use crate::interpreter::eval;
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+use crate::interpreter::{eval, Interpreter, InterpreterOptions};
 use crate::lexer::Lexer;
 use crate::parser::parse;
+use crate::runtime::Budget;
+use crate::stdlib::StdModule;
 use crate::value::EvalResult;
 #[macro_export]
 macro_rules! assert_match {
@@ -21,3 +26,38 @@ pub fn quick_result(input: &'static str) -> EvalResult {
     let ast = parse(&mut iter).unwrap();
     eval(&ast, None).0
 }
+
+//the printed value, panics on errors
+pub fn text(interpreter: &Interpreter, source: &str) -> String {
+    interpreter.eval_str(source).unwrap().to_string()
+}
+
+//the default modules and the given ones
+pub fn with_modules(modules: &[StdModule]) -> Interpreter {
+    let modules = [StdModule::DEFAULT.as_slice(), modules].concat();
+    Interpreter::with_options(InterpreterOptions{modules, ..Default::default()})
+}
+
+pub fn limited(budget: Budget) -> Interpreter {
+    Interpreter::with_options(InterpreterOptions{budget, ..Default::default()})
+}
+
+//unique per test process, nothing is created
+pub fn temp_path(name: &str, extension: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("kisp-{}-{}{}", name, std::process::id(), extension))
+}
+
+//writes the source to a temporary script and runs `binary run args... script`,
+//tests pass env!("CARGO_BIN_EXE_kisp") since only they know where cargo built it
+pub fn run_script(binary: &str, name: &str, args: &[&str], source: &str) -> Output {
+    let script = temp_path(name, ".kisp");
+    std::fs::write(&script, source).unwrap();
+    let output = Command::new(binary)
+        .arg("run")
+        .args(args)
+        .arg(&script)
+        .output()
+        .unwrap();
+    std::fs::remove_file(script).unwrap();
+    output
+}
@@ -1,8 +1,11 @@
 use std::fmt::{Debug, Formatter};
 use std::path::Display;
+use std::any::Any;
+use std::rc::Rc;
 use crate::ast::PosExpression;
-use crate::interpreter::eval_expression;
+use crate::interpreter::{eval_expression, split_keywords};
 use crate::scope::ScopeRef;
+use crate::value::convert::{FromKisp, IntoBuiltin};
 use crate::value::{EvalContext, EvalResult, EvalValue, ReferenceValue};
 use crate::value::error::{ErrorContext, EvalError};
 use crate::value::EvalValue::Reference;
@@ -15,8 +18,10 @@ pub struct BuiltInFunctionArg{
 
 pub struct BuiltInFunctionArgs{
     pub values: Vec<BuiltInFunctionArg>,
+    //`:name value` pairs for the keywords the builtin declared
+    pub named: Vec<(String, BuiltInFunctionArg)>,
 }
-pub type InternalCallback = fn(&'_ ScopeRef, EvalContext, BuiltInFunctionArgs) -> EvalResult;
+pub type InternalCallback = Rc<dyn Fn(&'_ ScopeRef, EvalContext, BuiltInFunctionArgs) -> EvalResult>;
 
 
 impl BuiltInFunctionArg{
@@ -31,6 +36,12 @@ impl BuiltInFunctionArg{
         }
     }
 
+    //evaluates to a native object holding a T
+    pub fn native<T: Any>(&self, scope: &ScopeRef) -> Result<Rc<T>, ErrorContext> {
+        let (value, _) = self.evaluated(scope)?;
+        Rc::<T>::from_kisp(&value).map_err(|e| e.trace(scope))
+    }
+
     pub fn try_expression<'c>(&'c self, scope: &ScopeRef) -> Result<&'c PosExpression, ErrorContext> {
         match &self.value {
             Reference(rc) => match rc.as_ref(){
@@ -46,10 +57,19 @@ impl BuiltInFunctionArg{
 impl BuiltInFunctionArgs{
     pub fn from(values: Vec<EvalValue>) -> BuiltInFunctionArgs{
         BuiltInFunctionArgs{
-            values: values.into_iter().map(|value| BuiltInFunctionArg{value}).collect()
+            values: values.into_iter().map(|value| BuiltInFunctionArg{value}).collect(),
+            named: vec![],
         }
     }
 
+    pub fn with_keywords(scope: &ScopeRef, values: Vec<EvalValue>, keywords: &[String]) -> Result<BuiltInFunctionArgs, ErrorContext> {
+        let (positional, named) = split_keywords(scope, values, keywords)?;
+        Ok(BuiltInFunctionArgs{
+            values: positional.into_iter().map(|value| BuiltInFunctionArg{value}).collect(),
+            named: named.into_iter().map(|(k, value)| (k, BuiltInFunctionArg{value})).collect(),
+        })
+    }
+
     pub fn eval_all(self, scope: &ScopeRef) -> Result<Vec<EvalValue>, ErrorContext> {
         self.values
             .into_iter()
@@ -64,11 +84,46 @@ impl BuiltInFunctionArgs{
             None => Err(EvalError::MissingArgument.trace(scope)),
         }
     }
+
+    pub fn try_named<'c>(&'c self, name: &str) -> Option<&'c BuiltInFunctionArg> {
+        self.named.iter().rev().find(|(k, _)| k == name).map(|(_, v)| v)
+    }
 }
 
+#[derive(Clone)]
 pub struct BuiltinFunction{
     pub callback: InternalCallback,
-    pub name: &'static str
+    pub name: String,
+    pub keywords: Vec<String>,
+    pub doc: Option<String>,
+    //(min, max) arguments, a `:keyword value` pair counts as one, None if the callback checks them itself
+    pub arity: Option<(usize, Option<usize>)>,
+}
+
+impl BuiltinFunction{
+    pub fn new(name: impl Into<String>, callback: impl Fn(&ScopeRef, EvalContext, BuiltInFunctionArgs) -> EvalResult + 'static) -> BuiltinFunction {
+        BuiltinFunction{callback: Rc::new(callback), name: name.into(), keywords: vec![], doc: None, arity: None}
+    }
+
+    //wraps a typed rust function, arguments are evaluated and converted automatically
+    pub fn wrap<Args>(name: impl Into<String>, function: impl IntoBuiltin<Args>) -> BuiltinFunction {
+        function.into_builtin(name.into())
+    }
+
+    pub fn with_doc(mut self, doc: impl Into<String>) -> BuiltinFunction {
+        self.doc = Some(doc.into());
+        self
+    }
+
+    pub fn with_arity(mut self, min: usize, max: Option<usize>) -> BuiltinFunction {
+        self.arity = Some((min, max));
+        self
+    }
+
+    pub fn with_keywords<S: Into<String>>(mut self, keywords: impl IntoIterator<Item=S>) -> BuiltinFunction {
+        self.keywords = keywords.into_iter().map(Into::into).collect();
+        self
+    }
 }
 
 impl Debug for BuiltinFunction{
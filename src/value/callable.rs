@@ -1,22 +1,76 @@
 use std::fmt;
 use std::fmt::{Debug, Display, Formatter};
 use std::rc::Rc;
-use crate::ast::PosExpression;
+use crate::ast::{PosExpression, REST_MARKER};
+use crate::lexer::langchars::KEYWORD_PREFIX;
 use crate::scope::ScopeRef;
 use crate::value::builtin::BuiltinFunction;
 use crate::value::{EvalValue, ReferenceValue};
 
+//lambdas have no name of their own, this is what errors call them
+pub const LAMBDA_NAME: &str = "lambda";
+
+#[derive(Debug, Clone, Default)]
+pub struct Parameters{
+    pub required: Vec<String>,
+    //defaults are evaluated at call time, after the preceding parameters are bound
+    pub optional: Vec<(String, PosExpression)>,
+    //passed as `:name value`, in any order after the positional arguments
+    pub keyword: Vec<(String, PosExpression)>,
+    pub rest: Option<String>,
+}
+
+impl Parameters{
+    pub fn positional(names: Vec<String>) -> Parameters {
+        Parameters{required: names, ..Default::default()}
+    }
+
+    pub fn min(&self) -> usize {
+        self.required.len()
+    }
+
+    pub fn max(&self) -> Option<usize> {
+        match self.rest {
+            Some(_) => None,
+            None => Some(self.required.len() + self.optional.len()),
+        }
+    }
+
+    pub fn accepts(&self, count: usize) -> bool {
+        count >= self.min() && self.max().is_none_or(|max| count <= max)
+    }
+}
+
+impl Display for Parameters{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let mut parts: Vec<String> = self.required.clone();
+        parts.extend(self.optional.iter().map(|(name, default)| format!("({} {})", name, default.exp)));
+        parts.extend(self.keyword.iter().map(|(name, default)| format!("{}{} {}", KEYWORD_PREFIX, name, default.exp)));
+        if let Some(rest) = &self.rest {
+            parts.push(format!("{} {}", REST_MARKER, rest));
+        }
+        f.write_fmt(format_args!("[{}]", parts.join(" ")))
+    }
+}
+
 #[derive(Debug)]
 pub struct Function{
     pub in_scope: ScopeRef,
     pub name: String,
-    pub arguments: Vec<String>,
+    pub arguments: Parameters,
     pub body: PosExpression,
+    //the string literal between the parameters and the body, if there was one
+    pub doc: Option<String>,
 }
 
 impl Function{
-    pub fn from(in_scope: ScopeRef, name: String, arguments: Vec<String>, body: &PosExpression) -> Function {
-        Function{in_scope, name, arguments, body: body.clone()}
+    pub fn from(in_scope: ScopeRef, name: String, arguments: Parameters, body: &PosExpression) -> Function {
+        Function{in_scope, name, arguments, body: body.clone(), doc: None}
+    }
+
+    pub fn with_doc(mut self, doc: Option<String>) -> Function {
+        self.doc = doc;
+        self
     }
 }
 
@@ -30,7 +84,7 @@ pub enum Callable{
 #[derive(Debug)]
 pub struct Lambda {
     pub in_scope: ScopeRef,
-    pub arguments: Vec<String>,
+    pub arguments: Parameters,
     pub body: PosExpression,
 }
 
@@ -42,6 +96,29 @@ pub struct TailCall{
 }
 
 
+impl Callable{
+    //what traces and profiles call it, lambdas go by where their body starts
+    pub fn label(&self) -> String {
+        match self {
+            Callable::Internal(b) => b.name.clone(),
+            Callable::Function(f) => f.name.clone(),
+            Callable::Lambda(l) => format!("{}@{}:{}", LAMBDA_NAME, l.body.cursor.line(), l.body.cursor.column()),
+        }
+    }
+
+    //signature on the first line, followed by whatever documentation there is
+    pub fn documentation(&self) -> String {
+        match self {
+            Callable::Internal(b) => b.doc.clone().unwrap_or_else(|| format!("({} ...)\nno documentation", b.name)),
+            Callable::Function(f) => match &f.doc {
+                Some(doc) => format!("({} {})\n{}", f.name, f.arguments, doc),
+                None => format!("({} {})", f.name, f.arguments),
+            },
+            Callable::Lambda(l) => format!("({} {})", LAMBDA_NAME, l.arguments),
+        }
+    }
+}
+
 impl Display for Callable{
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         match self {
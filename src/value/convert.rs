@@ -0,0 +1,283 @@
+use std::any::Any;
+use std::rc::Rc;
+
+use crate::scope::ScopeRef;
+use crate::value::builtin::BuiltinFunction;
+use crate::value::error::{ErrorContext, EvalError};
+use crate::value::list::List;
+use crate::value::numeric::Numeric;
+use crate::value::{EvalContext, EvalValue, ReferenceValue};
+
+//typed conversions between rust and kisp values, lets plain rust functions act as builtins
+
+pub trait FromKisp: Sized {
+    fn from_kisp(value: &EvalValue) -> Result<Self, EvalError>;
+}
+
+pub trait IntoKisp {
+    fn into_kisp(self) -> EvalValue;
+}
+
+fn mismatch(expected: impl Into<String>, value: &EvalValue) -> EvalError {
+    EvalError::TypeMismatch{expected: expected.into(), found: value.type_name()}
+}
+
+fn reference(value: ReferenceValue) -> EvalValue {
+    EvalValue::Reference(value.to_rc())
+}
+
+impl FromKisp for EvalValue {
+    fn from_kisp(value: &EvalValue) -> Result<Self, EvalError> {
+        Ok(value.clone())
+    }
+}
+
+impl IntoKisp for EvalValue {
+    fn into_kisp(self) -> EvalValue {
+        self
+    }
+}
+
+impl FromKisp for i32 {
+    fn from_kisp(value: &EvalValue) -> Result<Self, EvalError> {
+        match value {
+            EvalValue::Numeric(Numeric::Integer(i)) => Ok(*i),
+            _ => Err(mismatch("int", value)),
+        }
+    }
+}
+
+impl IntoKisp for i32 {
+    fn into_kisp(self) -> EvalValue {
+        EvalValue::Numeric(Numeric::Integer(self))
+    }
+}
+
+impl FromKisp for i64 {
+    fn from_kisp(value: &EvalValue) -> Result<Self, EvalError> {
+        i32::from_kisp(value).map(i64::from)
+    }
+}
+
+impl IntoKisp for i64 {
+    fn into_kisp(self) -> EvalValue {
+        //kisp integers are 32 bit, anything bigger degrades to a float
+        match i32::try_from(self) {
+            Ok(i) => i.into_kisp(),
+            Err(_) => (self as f64).into_kisp(),
+        }
+    }
+}
+
+impl FromKisp for f64 {
+    fn from_kisp(value: &EvalValue) -> Result<Self, EvalError> {
+        match value {
+            EvalValue::Numeric(Numeric::Integer(i)) => Ok(f64::from(*i)),
+            EvalValue::Numeric(Numeric::Floating(f)) => Ok(*f),
+            _ => Err(mismatch("numeric", value)),
+        }
+    }
+}
+
+impl IntoKisp for f64 {
+    fn into_kisp(self) -> EvalValue {
+        EvalValue::Numeric(Numeric::Floating(self))
+    }
+}
+
+impl FromKisp for bool {
+    //same truthiness as `if`
+    fn from_kisp(value: &EvalValue) -> Result<Self, EvalError> {
+        Ok(!matches!(value, EvalValue::Unit))
+    }
+}
+
+impl IntoKisp for bool {
+    fn into_kisp(self) -> EvalValue {
+        if self { EvalValue::True } else { EvalValue::Unit }
+    }
+}
+
+impl IntoKisp for () {
+    fn into_kisp(self) -> EvalValue {
+        EvalValue::Unit
+    }
+}
+
+impl FromKisp for String {
+    fn from_kisp(value: &EvalValue) -> Result<Self, EvalError> {
+        match value {
+            EvalValue::Reference(r) => match r.as_ref() {
+                ReferenceValue::String(s) => Ok(s.clone()),
+                _ => Err(mismatch("string", value)),
+            },
+            _ => Err(mismatch("string", value)),
+        }
+    }
+}
+
+impl IntoKisp for String {
+    fn into_kisp(self) -> EvalValue {
+        reference(ReferenceValue::String(self))
+    }
+}
+
+impl IntoKisp for &str {
+    fn into_kisp(self) -> EvalValue {
+        self.to_string().into_kisp()
+    }
+}
+
+impl<T: Any> FromKisp for Rc<T> {
+    fn from_kisp(value: &EvalValue) -> Result<Self, EvalError> {
+        let expected = || format!("native {}", std::any::type_name::<T>());
+        match value {
+            EvalValue::Reference(r) => match r.as_ref() {
+                ReferenceValue::Native(n) => n.downcast::<T>().ok_or_else(|| mismatch(expected(), value)),
+                _ => Err(mismatch(expected(), value)),
+            },
+            _ => Err(mismatch(expected(), value)),
+        }
+    }
+}
+
+fn list_values(value: &EvalValue) -> Result<Vec<EvalValue>, EvalError> {
+    List::from_kisp(value).map(|l| l.iterator().collect())
+}
+
+impl FromKisp for Numeric {
+    fn from_kisp(value: &EvalValue) -> Result<Self, EvalError> {
+        match value {
+            EvalValue::Numeric(n) => Ok(n.clone()),
+            _ => Err(mismatch("numeric", value)),
+        }
+    }
+}
+
+impl IntoKisp for Numeric {
+    fn into_kisp(self) -> EvalValue {
+        EvalValue::Numeric(self)
+    }
+}
+
+impl FromKisp for List {
+    fn from_kisp(value: &EvalValue) -> Result<Self, EvalError> {
+        match value {
+            EvalValue::Reference(r) => match r.as_ref() {
+                ReferenceValue::List(l) => Ok(l.clone()),
+                _ => Err(mismatch("list", value)),
+            },
+            _ => Err(mismatch("list", value)),
+        }
+    }
+}
+
+impl IntoKisp for List {
+    fn into_kisp(self) -> EvalValue {
+        reference(ReferenceValue::List(self))
+    }
+}
+
+impl<T: FromKisp> FromKisp for Vec<T> {
+    fn from_kisp(value: &EvalValue) -> Result<Self, EvalError> {
+        list_values(value)?.iter().map(T::from_kisp).collect()
+    }
+}
+
+impl<T: IntoKisp> IntoKisp for Vec<T> {
+    fn into_kisp(self) -> EvalValue {
+        reference(ReferenceValue::List(List::from(self.into_iter().map(IntoKisp::into_kisp).collect())))
+    }
+}
+
+impl<T: FromKisp> FromKisp for Option<T> {
+    fn from_kisp(value: &EvalValue) -> Result<Self, EvalError> {
+        match value {
+            EvalValue::Unit => Ok(None),
+            v => T::from_kisp(v).map(Some),
+        }
+    }
+}
+
+impl<T: IntoKisp> IntoKisp for Option<T> {
+    fn into_kisp(self) -> EvalValue {
+        match self {
+            Some(v) => v.into_kisp(),
+            None => EvalValue::Unit,
+        }
+    }
+}
+
+//tuples are lists of exactly that length
+macro_rules! impl_tuple {
+    ($len: expr, $($t: ident $v: ident),+) => {
+        impl<$($t: FromKisp),+> FromKisp for ($($t,)+) {
+            fn from_kisp(value: &EvalValue) -> Result<Self, EvalError> {
+                let values = list_values(value)?;
+                if values.len() != $len {
+                    return Err(mismatch(format!("list of {}", $len), value));
+                }
+                let mut values = values.iter();
+                $(let $v = $t::from_kisp(values.next().unwrap())?;)+
+                Ok(($($v,)+))
+            }
+        }
+
+        impl<$($t: IntoKisp),+> IntoKisp for ($($t,)+) {
+            fn into_kisp(self) -> EvalValue {
+                let ($($v,)+) = self;
+                reference(ReferenceValue::List(List::from(vec![$($v.into_kisp()),+])))
+            }
+        }
+    }
+}
+
+impl_tuple!(2, A a, B b);
+impl_tuple!(3, A a, B b, C c);
+impl_tuple!(4, A a, B b, C c, D d);
+
+//shared by the wrappers below and the #[builtin] macro
+pub fn check_arity(scope: &ScopeRef, function: &str, actual: usize, min: usize, max: Option<usize>) -> Result<(), ErrorContext> {
+    if actual < min || max.is_some_and(|max| actual > max) {
+        return Err(EvalError::ArityMismatch{function: function.to_string(), min, max, actual}.trace(scope));
+    }
+    Ok(())
+}
+
+pub fn convert_arg<T: FromKisp>(scope: &ScopeRef, value: &EvalValue) -> Result<T, ErrorContext> {
+    T::from_kisp(value).map_err(|e| e.trace(scope))
+}
+
+//rust functions whose arguments and result convert to and from kisp values
+pub trait IntoBuiltin<Args> {
+    fn into_builtin(self, name: String) -> BuiltinFunction;
+}
+
+macro_rules! impl_into_builtin {
+    ($($t: ident $v: ident),*) => {
+        impl<F, R, $($t),*> IntoBuiltin<($($t,)*)> for F
+        where F: Fn($($t),*) -> R + 'static, R: IntoKisp, $($t: FromKisp),*
+        {
+            fn into_builtin(self, name: String) -> BuiltinFunction {
+                let function = name.clone();
+                let expected = <[&str]>::len(&[$(stringify!($t)),*]);
+                BuiltinFunction::new(name, move |scope, _ctx, args| {
+                    let values = args.eval_all(scope)?;
+                    check_arity(scope, &function, values.len(), expected, Some(expected))?;
+                    #[allow(unused_mut, unused_variables)]
+                    let mut values = values.iter();
+                    $(let $v = convert_arg::<$t>(scope, values.next().unwrap())?;)*
+                    Ok((self($($v),*).into_kisp(), EvalContext::none()))
+                })
+                .with_arity(expected, Some(expected))
+            }
+        }
+    }
+}
+
+impl_into_builtin!();
+impl_into_builtin!(A a);
+impl_into_builtin!(A a, B b);
+impl_into_builtin!(A a, B b, C c);
+impl_into_builtin!(A a, B b, C c, D d);
+impl_into_builtin!(A a, B b, C c, D d, E e);
@@ -1,20 +1,44 @@
-use crate::lexer::Cursor;
+use std::fmt::{Display, Formatter};
+
+use crate::json::JsonError;
 use crate::scope::ScopeRef;
 use crate::stacktrace::StackTrace;
 
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Resource{
+    Fuel,
+    Time,
+    ListCells,
+}
+
 #[derive(Debug)]
 pub enum EvalError{
     Other(String),
     UnknownSymbol(String),
     CallingNonCallable,
     InvalidType,
+    TypeMismatch{expected: String, found: &'static str},
     MissingArgument,
+    UnknownMethod{type_name: String, method: String},
+    ArityMismatch{function: String, min: usize, max: Option<usize>, actual: usize},
     NotImplemented,
     Reassignment,
     StackOverflow,
+    ResourceExhausted(Resource),
+    Interrupted,
+    //the script called exit, the host decides what ending the process means
+    Exit(i32),
+    //failed os call, the message includes the path involved
+    Io(String),
+    Json(JsonError),
 }
 
 impl EvalError{
+    //budget, interrupt and exit aborts must reach the host, scripts can't swallow them
+    pub fn is_catchable(&self) -> bool {
+        !matches!(self, EvalError::Interrupted | EvalError::ResourceExhausted(_) | EvalError::Exit(_))
+    }
+
     pub fn trace(self, scope: &ScopeRef) -> ErrorContext {
         ErrorContext{
             error: self,
@@ -23,8 +47,47 @@ impl EvalError{
     }
 }
 
+impl Display for EvalError{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EvalError::Other(message) | EvalError::Io(message) => f.write_str(message),
+            EvalError::UnknownSymbol(s) => f.write_fmt(format_args!("unknown symbol `{}`", s)),
+            EvalError::CallingNonCallable => f.write_str("calling a value that isn't callable"),
+            EvalError::InvalidType => f.write_str("invalid type"),
+            EvalError::TypeMismatch{expected, found} => f.write_fmt(format_args!("expected {}, found {}", expected, found)),
+            EvalError::MissingArgument => f.write_str("missing argument"),
+            EvalError::UnknownMethod{type_name, method} => f.write_fmt(format_args!("{} has no method `{}`", type_name, method)),
+            EvalError::ArityMismatch{function, min, max: Some(max), actual} if min == max =>
+                f.write_fmt(format_args!("`{}` expects {} argument(s), got {}", function, min, actual)),
+            EvalError::ArityMismatch{function, min, max: Some(max), actual} =>
+                f.write_fmt(format_args!("`{}` expects {} to {} arguments, got {}", function, min, max, actual)),
+            EvalError::ArityMismatch{function, min, max: None, actual} =>
+                f.write_fmt(format_args!("`{}` expects at least {} argument(s), got {}", function, min, actual)),
+            EvalError::NotImplemented => f.write_str("not implemented"),
+            EvalError::Reassignment => f.write_str("reassignment"),
+            EvalError::StackOverflow => f.write_str("stack overflow"),
+            EvalError::ResourceExhausted(Resource::Fuel) => f.write_str("out of fuel"),
+            EvalError::ResourceExhausted(Resource::Time) => f.write_str("time limit exceeded"),
+            EvalError::ResourceExhausted(Resource::ListCells) => f.write_str("list cell limit exceeded"),
+            EvalError::Interrupted => f.write_str("interrupted"),
+            EvalError::Exit(code) => f.write_fmt(format_args!("exit with status {}", code)),
+            EvalError::Json(e) => f.write_fmt(format_args!("invalid json: {}", e)),
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct ErrorContext{
     error: EvalError,
     stack_trace: Option<StackTrace>
 }
+
+impl ErrorContext{
+    pub fn error(&self) -> &EvalError {
+        &self.error
+    }
+
+    pub fn stack_trace(&self) -> Option<&StackTrace> {
+        self.stack_trace.as_ref()
+    }
+}
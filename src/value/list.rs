@@ -1,16 +1,30 @@
+use std::cell::Cell;
 use std::fmt;
 use std::fmt::{Display, Formatter};
 use std::rc::Rc;
 use crate::value::EvalValue;
 
 
+thread_local! {
+    //every cons cell ever made on this thread, the runtime budgets the difference
+    static ALLOCATED_CELLS: Cell<usize> = const { Cell::new(0) };
+}
+
+pub fn allocated_cells() -> usize {
+    ALLOCATED_CELLS.with(|c| c.get())
+}
+
+fn count_cell() {
+    ALLOCATED_CELLS.with(|c| c.set(c.get() + 1));
+}
+
 #[derive(Debug)]
 struct Con {
     value: EvalValue,
     next: Option<Rc<Con>>, //RC, so multiple lists can have the same values
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct List(Option<Rc<Con>>);
 
 impl List{
@@ -42,6 +56,7 @@ impl List{
     }
 
     pub fn prepended(&self, value: EvalValue) -> List{
+        count_cell();
         List(Some(Rc::new(Con{ value, next: self.0.clone()})))
     }
 }
@@ -51,9 +66,10 @@ impl FromIterator<EvalValue> for List{
     fn from_iter<T: IntoIterator<Item=EvalValue>>(iter: T) -> Self {
         let iterator = iter.into_iter();
         let head_opt = iterator
-            .fold(None, |next,value|
+            .fold(None, |next,value| {
+                count_cell();
                 Some(Rc::new(Con{value, next}))
-            );
+            });
         List(head_opt)
     }
 }
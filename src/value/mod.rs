@@ -2,23 +2,23 @@ use std::fmt;
 use std::fmt::{Debug, Display, Formatter, Write};
 
 use std::rc::Rc;
-use error::EvalError;
 use crate::ast::{PosExpression, SExpression};
-
-
-use crate::lexer::Cursor;
-use crate::stacktrace::StackTrace;
+use crate::lexer::langchars::KEYWORD_PREFIX;
 use crate::value::numeric::Numeric;
 
 use crate::value::callable::{Callable, TailCall};
 use crate::value::error::ErrorContext;
 use crate::value::list::List;
+use crate::value::native::NativeObject;
 
 pub mod list;
 pub mod callable;
 pub mod builtin;
 pub mod numeric;
 pub mod error;
+pub mod convert;
+pub mod native;
+pub mod repr;
 
 #[derive(Debug, Clone)]
 pub enum EvalValue{
@@ -36,6 +36,9 @@ pub enum ReferenceValue {
     //False, //really just nil
     CallableValue(Callable),
     List(List),
+    Keyword(String),
+    String(String),
+    Native(NativeObject),
     Expression(PosExpression), //used for macros and builtins
 
     //TODO: does this even fit here? I don't wanna complicate the code too much though
@@ -50,11 +53,72 @@ impl ReferenceValue {
     }
 }
 
+impl ReferenceValue {
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            ReferenceValue::CallableValue(Callable::Internal(_)) => "builtin",
+            ReferenceValue::CallableValue(Callable::Function(_)) => "function",
+            ReferenceValue::CallableValue(Callable::Lambda(_)) => "lambda",
+            ReferenceValue::List(_) => "list",
+            ReferenceValue::Keyword(_) => "keyword",
+            ReferenceValue::String(_) => "string",
+            ReferenceValue::Native(_) => "native",
+            ReferenceValue::Expression(_) => "expression",
+            ReferenceValue::TailCallValue(_) => "tail-call",
+        }
+    }
+}
+
+impl EvalValue{
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            EvalValue::Numeric(Numeric::Integer(_)) => "int",
+            EvalValue::Numeric(Numeric::Floating(_)) => "float",
+            EvalValue::Unit => "unit",
+            EvalValue::True => "true",
+            EvalValue::Reference(r) => r.type_name(),
+        }
+    }
+
+    //name of the keyword, whether it's still a literal in the source or already evaluated
+    pub fn keyword(&self) -> Option<&str> {
+        match self {
+            EvalValue::Reference(r) => match r.as_ref() {
+                ReferenceValue::Keyword(k) => Some(k.as_str()),
+                ReferenceValue::Expression(PosExpression{exp: SExpression::Keyword(k), ..}) => Some(k.as_str()),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+}
+
+//data compares by value, functions and host objects by identity
+impl PartialEq for EvalValue{
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (EvalValue::Numeric(a), EvalValue::Numeric(b)) => a == b,
+            (EvalValue::Unit, EvalValue::Unit) | (EvalValue::True, EvalValue::True) => true,
+            (EvalValue::Reference(a), EvalValue::Reference(b)) => Rc::ptr_eq(a, b) || match (a.as_ref(), b.as_ref()) {
+                (ReferenceValue::String(a), ReferenceValue::String(b)) => a == b,
+                (ReferenceValue::Keyword(a), ReferenceValue::Keyword(b)) => a == b,
+                (ReferenceValue::List(a), ReferenceValue::List(b)) => a.iterator().eq(b.iterator()),
+                (ReferenceValue::Expression(a), ReferenceValue::Expression(b)) => a.exp == b.exp,
+                _ => false,
+            },
+            _ => false,
+        }
+    }
+}
+
 impl Display for ReferenceValue {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         match self {
             ReferenceValue::CallableValue(c) => Display::fmt(c, f),
             ReferenceValue::List(list) => Display::fmt(list, f),
+            ReferenceValue::Keyword(k) => f.write_fmt(format_args!("{}{}", KEYWORD_PREFIX, k)),
+            ReferenceValue::String(s) => f.write_str(s),
+            ReferenceValue::Native(n) => f.write_fmt(format_args!("<native: {}>", n.type_name())),
             ReferenceValue::TailCallValue(_) => f.write_str("<tail-call>"),
             ReferenceValue::Expression(PosExpression{exp,..}) => f.write_fmt(format_args!("'{}", exp)),
         }
@@ -77,6 +141,7 @@ impl Display for EvalValue{
 pub type EvalResult = Result<(EvalValue, EvalContext), ErrorContext>;
 
 //used only for tail recursion ... for now
+#[derive(Debug)]
 pub struct EvalContext{
     pub possible_tail: bool
 }
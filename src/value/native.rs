@@ -0,0 +1,69 @@
+use std::any::Any;
+use std::collections::HashMap;
+use std::fmt::{Debug, Formatter};
+use std::rc::Rc;
+
+use crate::value::builtin::BuiltinFunction;
+use crate::value::{EvalValue, ReferenceValue};
+
+//host objects handed to scripts, kisp only sees the type name and the methods
+
+pub struct NativeType{
+    pub name: String,
+    methods: HashMap<String, BuiltinFunction>,
+}
+
+impl NativeType{
+    pub fn new(name: impl Into<String>) -> NativeType {
+        NativeType{name: name.into(), methods: HashMap::new()}
+    }
+
+    //methods get the object itself as their first argument
+    pub fn with_method(mut self, method: BuiltinFunction) -> NativeType {
+        self.methods.insert(method.name.clone(), method);
+        self
+    }
+
+    pub fn method(&self, name: &str) -> Option<&BuiltinFunction> {
+        self.methods.get(name)
+    }
+
+    pub fn method_names(&self) -> impl Iterator<Item=&String> {
+        self.methods.keys()
+    }
+}
+
+pub struct NativeObject{
+    pub kind: Rc<NativeType>,
+    value: Rc<dyn Any>,
+}
+
+impl NativeObject{
+    pub fn new<T: Any>(kind: &Rc<NativeType>, value: T) -> NativeObject {
+        NativeObject{kind: kind.clone(), value: Rc::new(value)}
+    }
+
+    pub fn type_name(&self) -> &str {
+        &self.kind.name
+    }
+
+    pub fn downcast<T: Any>(&self) -> Option<Rc<T>> {
+        self.value.clone().downcast::<T>().ok()
+    }
+
+    pub fn is<T: Any>(&self) -> bool {
+        self.value.is::<T>()
+    }
+}
+
+impl Debug for NativeObject{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!("(native {})", self.kind.name))
+    }
+}
+
+impl EvalValue{
+    pub fn native<T: Any>(kind: &Rc<NativeType>, value: T) -> EvalValue {
+        EvalValue::Reference(ReferenceValue::Native(NativeObject::new(kind, value)).to_rc())
+    }
+}
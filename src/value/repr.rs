@@ -0,0 +1,112 @@
+use crate::ast::{PosExpression, SExpression};
+use crate::lexer::{escape_string, Lexer, TokenValue};
+use crate::lexer::langchars::KEYWORD_PREFIX;
+use crate::parser::parse;
+use crate::value::error::EvalError;
+use crate::value::list::List;
+use crate::value::numeric::Numeric;
+use crate::value::{EvalValue, ReferenceValue};
+
+//readable printing of data values, read(repr(v)) == v for unit, true, numbers, strings, keywords and lists of those
+
+const LIST_CONSTRUCTOR: &str = "list";
+const TRUE_SYMBOL: &str = "true";
+//the parser recurses once per level, untrusted text must not be able to overflow the stack
+const MAX_DEPTH: usize = 512;
+
+impl EvalValue{
+    //whether repr produces something read can turn back into this value
+    pub fn is_data(&self) -> bool {
+        match self {
+            EvalValue::Unit | EvalValue::True | EvalValue::Numeric(_) => true,
+            EvalValue::Reference(r) => match r.as_ref() {
+                ReferenceValue::String(_) | ReferenceValue::Keyword(_) | ReferenceValue::Expression(_) => true,
+                ReferenceValue::List(list) => list.iterator().all(|v| v.is_data()),
+                _ => false,
+            },
+        }
+    }
+
+    pub fn repr(&self) -> String {
+        match self {
+            EvalValue::Unit => "()".to_string(),
+            EvalValue::True => TRUE_SYMBOL.to_string(),
+            EvalValue::Numeric(Numeric::Integer(i)) => i.to_string(),
+            //debug formatting always keeps the decimal point and every digit
+            EvalValue::Numeric(Numeric::Floating(f)) => format!("{:?}", f),
+            EvalValue::Reference(r) => match r.as_ref() {
+                ReferenceValue::String(s) => escape_string(s),
+                ReferenceValue::Keyword(k) => format!("{}{}", KEYWORD_PREFIX, k),
+                ReferenceValue::List(list) => {
+                    let items = std::iter::once(LIST_CONSTRUCTOR.to_string())
+                        .chain(list.iterator().map(|v| v.repr()))
+                        .collect::<Vec<String>>();
+                    format!("({})", items.join(" "))
+                }
+                ReferenceValue::Expression(PosExpression{exp, ..}) => format!("(quote {})", exp),
+                //functions and host objects have no literal syntax
+                other => other.to_string(),
+            },
+        }
+    }
+}
+
+fn reference(value: ReferenceValue) -> EvalValue {
+    EvalValue::Reference(value.to_rc())
+}
+
+//the data value an expression spells out, None if it would need evaluating
+fn literal(expression: &PosExpression) -> Option<EvalValue> {
+    match &expression.exp {
+        SExpression::Number(n) => Some(EvalValue::Numeric(n.clone())),
+        SExpression::String(s) => Some(reference(ReferenceValue::String(s.clone()))),
+        SExpression::Keyword(k) => Some(reference(ReferenceValue::Keyword(k.clone()))),
+        SExpression::Symbol(s) if s == TRUE_SYMBOL => Some(EvalValue::True),
+        SExpression::List(items) => match items.split_first() {
+            None => Some(EvalValue::Unit),
+            Some((head, rest)) if matches!(&head.exp, SExpression::Symbol(s) if s == LIST_CONSTRUCTOR) => {
+                let values = rest.iter().map(literal).collect::<Option<Vec<EvalValue>>>()?;
+                Some(reference(ReferenceValue::List(List::from(values))))
+            }
+            Some((head, rest)) if matches!(&head.exp, SExpression::Symbol(s) if s == "quote") && rest.len() == 1 =>
+                Some(reference(ReferenceValue::Expression(rest[0].clone()))),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+//whether parentheses and brackets anywhere in the source nest deeper than MAX_DEPTH,
+//stops at the first level too deep so hostile input isn't lexed to the end
+fn too_deep(source: &str) -> bool {
+    let mut depth: usize = 0;
+    for token in Lexer::from_text(source) {
+        match token.value {
+            TokenValue::ParenthesisOpen | TokenValue::BracketOpen => {
+                depth += 1;
+                if depth > MAX_DEPTH {
+                    return true;
+                }
+            }
+            TokenValue::ParenthesisClose | TokenValue::BracketClose => depth = depth.saturating_sub(1),
+            TokenValue::EOF => break,
+            _ => {}
+        }
+    }
+    false
+}
+
+//parses exactly one form, data literals become values and anything else a quoted expression
+pub fn read_value(source: &str) -> Result<EvalValue, EvalError> {
+    if too_deep(source) {
+        return Err(EvalError::Other(format!("read nests deeper than {} levels", MAX_DEPTH)));
+    }
+    let ast = parse(&mut Lexer::from_text(source).into_iter())
+        .map_err(|e| EvalError::Other(e.to_string()))?;
+    let expression = match ast.exp {
+        SExpression::Block(mut forms) if forms.len() == 1 => forms.remove(0),
+        SExpression::Block(forms) => return Err(EvalError::Other(format!("read expects a single form, found {}", forms.len()))),
+        _ => ast,
+    };
+    Ok(literal(&expression).unwrap_or_else(|| reference(ReferenceValue::Expression(expression))))
+}
@@ -0,0 +1,44 @@
+use kisp::assert_match;
+use kisp::testutils::quick_result;
+use kisp::value::EvalValue;
+use kisp::value::error::EvalError;
+use kisp::value::numeric::Numeric;
+
+#[test]
+fn too_few_arguments(){
+    let err = quick_result("(fn add [a b] (+ a b)) (add 1)").unwrap_err();
+    assert_match!(
+        err.error(),
+        EvalError::ArityMismatch{function, min: 2, max: Some(2), actual: 1} if function == "add"
+    );
+}
+
+#[test]
+fn too_many_arguments(){
+    let err = quick_result("((lambda [a] a) 1 2)").unwrap_err();
+    assert_match!(err.error(), EvalError::ArityMismatch{min: 1, max: Some(1), actual: 2, ..});
+}
+
+#[test]
+fn rest_parameter(){
+    let (value, _) = quick_result("(fn count [a & rest] (fold a (lambda [acc _] (+ acc 1)) rest)) (count 10 1 2 3)").unwrap();
+    assert_match!(value, EvalValue::Numeric(Numeric::Integer(13)));
+}
+
+#[test]
+fn optional_parameter(){
+    let (value, _) = quick_result("(fn scale [x (factor 2) (offset factor)] (+ (* x factor) offset)) (list (scale 5) (scale 5 3) (scale 5 3 1))").unwrap();
+    assert_eq!(value.to_string(), "<list: 12 18 16>");
+}
+
+#[test]
+fn optional_with_tail_recursion(){
+    let (value, _) = quick_result("(fn sum [n (acc 0)] (if (>= 0 n) acc (sum (- n 1) (+ acc n)))) (sum 1000)").unwrap();
+    assert_match!(value, EvalValue::Numeric(Numeric::Integer(500500)));
+}
+
+#[test]
+fn args_builtin(){
+    let (value, _) = quick_result("(fn all [a & _] [(let x 1) (args)]) (all 1 2 3)").unwrap();
+    assert_eq!(value.to_string(), "<list: 1 2 3>");
+}
@@ -0,0 +1,84 @@
+use std::time::{Duration, Instant};
+
+use kisp::assert_match;
+use kisp::interpreter::{Interpreter, InterpreterError};
+use kisp::runtime::Budget;
+use kisp::testutils::{limited, run_script};
+use kisp::value::EvalValue;
+use kisp::value::error::{EvalError, Resource};
+use kisp::value::numeric::Numeric;
+
+fn exhausted(result: Result<EvalValue, InterpreterError>) -> Resource {
+    match result {
+        Err(InterpreterError::Eval(e)) => match e.error() {
+            EvalError::ResourceExhausted(r) => *r,
+            other => panic!("unexpected error {:?}", other),
+        },
+        other => panic!("expected an exhausted budget, got {:?}", other.map(|v| v.to_string())),
+    }
+}
+
+#[test]
+fn fuel_stops_endless_recursion(){
+    let interpreter = limited(Budget{fuel: Some(10_000), ..Default::default()});
+    interpreter.eval_str("(fn spin [] (spin))").unwrap();
+    assert_eq!(exhausted(interpreter.eval_str("(spin)")), Resource::Fuel);
+
+    //the budget is per evaluation, the interpreter keeps working afterwards
+    let value = interpreter.eval_str("(+ 1 2)").unwrap();
+    assert_match!(value, EvalValue::Numeric(Numeric::Integer(3)));
+    assert_eq!(exhausted(interpreter.call("spin", vec![])), Resource::Fuel);
+}
+
+#[test]
+fn deadline_stops_endless_loop(){
+    let interpreter = limited(Budget{time: Some(Duration::from_millis(50)), ..Default::default()});
+    let start = Instant::now();
+    assert_eq!(exhausted(interpreter.eval_str("(fn spin [n] (spin (+ n 1))) (spin 0)")), Resource::Time);
+    assert!(start.elapsed() < Duration::from_secs(5));
+}
+
+#[test]
+fn list_cell_cap(){
+    let interpreter = limited(Budget{list_cells: Some(100), ..Default::default()});
+    interpreter.eval_str("(fn grow [l] (grow (cons 1 l)))").unwrap();
+    assert_eq!(exhausted(interpreter.eval_str("(grow (list))")), Resource::ListCells);
+    assert_match!(interpreter.eval_str("(list 1 2 3)"), Ok(EvalValue::Reference(_)));
+}
+
+#[test]
+fn unlimited_by_default(){
+    let interpreter = Interpreter::new();
+    let value = interpreter.eval_str("(fn count [n] (if (>= 0 n) 0 (count (- n 1)))) (count 50000)").unwrap();
+    assert_match!(value, EvalValue::Numeric(Numeric::Integer(0)));
+}
+
+#[test]
+fn interrupt_cancels_and_keeps_definitions(){
+    let interpreter = Interpreter::new();
+    interpreter.eval_str("(let answer 42) (fn spin [n] (spin (+ n 1)))").unwrap();
+    let handle = interpreter.interrupt_handle();
+    let interrupter = std::thread::spawn(move || {
+        std::thread::sleep(Duration::from_millis(50));
+        handle.store(true, std::sync::atomic::Ordering::Relaxed);
+    });
+    let err = interpreter.eval_str("(spin 0)").unwrap_err();
+    interrupter.join().unwrap();
+    assert_match!(err, InterpreterError::Eval(e) if matches!(e.error(), EvalError::Interrupted));
+    assert_match!(interpreter.eval_str("answer"), Ok(EvalValue::Numeric(Numeric::Integer(42))));
+}
+
+#[test]
+fn try_cannot_swallow_exhaustion(){
+    let interpreter = limited(Budget{fuel: Some(1_000), ..Default::default()});
+    interpreter.eval_str("(fn spin [] (spin))").unwrap();
+    assert_eq!(exhausted(interpreter.eval_str("(try (spin) (lambda [e] 0))")), Resource::Fuel);
+}
+
+#[test]
+fn list_cell_flag(){
+    let source = "(fn grow [xs] (grow (cons 1 xs))) (grow (list))";
+    let output = run_script(env!("CARGO_BIN_EXE_kisp"), "cells", &["--max-list-cells", "100"], source);
+    assert!(!output.status.success());
+    assert!(String::from_utf8(output.stderr).unwrap().contains("list cell limit exceeded"));
+}
@@ -0,0 +1,62 @@
+use kisp::assert_match;
+use kisp::interpreter::{Interpreter, InterpreterError};
+use kisp::value::builtin::BuiltinFunction;
+use kisp::value::convert::{FromKisp, IntoKisp};
+use kisp::value::EvalValue;
+use kisp::value::error::EvalError;
+use kisp::value::numeric::Numeric;
+
+fn scaled_sum(factor: i64, values: Vec<f64>) -> f64 {
+    values.iter().sum::<f64>() * factor as f64
+}
+
+fn greet(name: String, excited: Option<bool>) -> String {
+    format!("hello {}{}", name, if excited.unwrap_or(false) { "!" } else { "" })
+}
+
+#[test]
+fn string_literals(){
+    let interpreter = Interpreter::new();
+    let value = interpreter.eval_str(r#"(list "a \"quoted\"\tword" (is-string? "") (is-string? 1))"#).unwrap();
+    assert_eq!(value.to_string(), "<list: a \"quoted\"\tword true unit>");
+    assert_match!(interpreter.eval_str("\"open"), Err(InterpreterError::Parser(_)));
+}
+
+#[test]
+fn round_trips(){
+    let pair = (1, String::from("two"));
+    assert_eq!(<(i32, String)>::from_kisp(&pair.clone().into_kisp()).unwrap(), pair);
+    let nested = vec![vec![1.5, 2.0], vec![]];
+    assert_eq!(Vec::<Vec<f64>>::from_kisp(&nested.clone().into_kisp()).unwrap(), nested);
+    assert_eq!(Option::<i32>::from_kisp(&None::<i32>.into_kisp()).unwrap(), None);
+    assert_eq!(i64::from_kisp(&EvalValue::Numeric(Numeric::Integer(-3))).unwrap(), -3);
+    assert_match!(i64::MAX.into_kisp(), EvalValue::Numeric(Numeric::Floating(_)));
+    assert!(bool::from_kisp(&1.into_kisp()).unwrap());
+    assert_match!(
+        i32::from_kisp(&"x".into_kisp()),
+        Err(EvalError::TypeMismatch{expected, found: "string"}) if expected == "int"
+    );
+}
+
+#[test]
+fn wrapped_functions(){
+    let interpreter = Interpreter::new();
+    interpreter.register(BuiltinFunction::wrap("scaled-sum", scaled_sum));
+    interpreter.register(BuiltinFunction::wrap("greet", greet));
+    interpreter.register(BuiltinFunction::wrap("answer", || 42));
+
+    let value = interpreter.eval_str("(scaled-sum (answer) (list 1 2.5))").unwrap();
+    assert_match!(value, EvalValue::Numeric(Numeric::Floating(f)) if f == 147.0);
+    let value = interpreter.eval_str(r#"(list (greet "bob" ()) (greet "amy" true))"#).unwrap();
+    assert_eq!(value.to_string(), "<list: hello bob hello amy!>");
+}
+
+#[test]
+fn wrapped_function_errors(){
+    let interpreter = Interpreter::new();
+    interpreter.register(BuiltinFunction::wrap("scaled-sum", scaled_sum));
+    let err = interpreter.eval_str("(scaled-sum 1)").unwrap_err();
+    assert_match!(err, InterpreterError::Eval(e) if matches!(e.error(), EvalError::ArityMismatch{min: 2, actual: 1, ..}));
+    let err = interpreter.eval_str("(scaled-sum 1 (list 1 :two))").unwrap_err();
+    assert_match!(err, InterpreterError::Eval(e) if matches!(e.error(), EvalError::TypeMismatch{found: "keyword", ..}));
+}
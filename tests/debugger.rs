@@ -0,0 +1,119 @@
+use std::cell::{Cell, RefCell};
+use std::collections::VecDeque;
+use std::rc::Rc;
+
+use kisp::ast::PosExpression;
+use kisp::debugger::{locals, Breakpoint, Debugger, Frontend, Step, Stepper};
+use kisp::interpreter::{Interpreter, InterpreterError};
+use kisp::scope::ScopeRef;
+use kisp::value::EvalResult;
+use kisp::value::error::EvalError;
+
+//answers every pause with the next scripted step and remembers where it paused
+struct Script{
+    steps: RefCell<VecDeque<Step>>,
+    pauses: RefCell<Vec<String>>,
+    locals: RefCell<Vec<String>>,
+}
+
+impl Frontend for Script{
+    fn paused(&self, scope: &ScopeRef, expression: &PosExpression) -> Result<Step, EvalError> {
+        self.pauses.borrow_mut().push(format!("{}:{} {}", expression.cursor.line(), expression.cursor.column(), expression.exp));
+        self.locals.borrow_mut().push(locals(scope).iter().map(|(n, v)| format!("{}={}", n, v)).collect::<Vec<_>>().join(" "));
+        self.steps.borrow_mut().pop_front().ok_or(EvalError::Interrupted)
+    }
+}
+
+fn debugged(steps: Vec<Step>) -> (Interpreter, Rc<Stepper<Script>>) {
+    let interpreter = Interpreter::new();
+    let stepper = Rc::new(Stepper::new(Script{steps: RefCell::new(steps.into()), pauses: Default::default(), locals: Default::default()}));
+    interpreter.set_debugger(Some(stepper.clone()));
+    (interpreter, stepper)
+}
+
+fn pauses(stepper: &Stepper<Script>) -> Vec<String> {
+    stepper.frontend().pauses.borrow().clone()
+}
+
+#[test]
+fn function_breakpoints(){
+    let (interpreter, stepper) = debugged(vec![Step::Continue; 3]);
+    interpreter.eval_str("(fn square [x] (* x x))").unwrap();
+    stepper.add_breakpoint(Breakpoint::parse("square"));
+    assert_eq!(interpreter.eval_str("(fold 0 (lambda [a b] (+ a (square b))) (list 1 2 3))").unwrap().to_string(), "14");
+    assert_eq!(pauses(&stepper), vec!["1:16 (* x x)"; 3]);
+    assert_eq!(*stepper.frontend().locals.borrow(), vec!["x=1", "x=2", "x=3"]);
+}
+
+#[test]
+fn stepping_in_over_and_out(){
+    let (interpreter, stepper) = debugged(vec![Step::In, Step::Over, Step::In, Step::Over, Step::In, Step::Out, Step::Continue]);
+    interpreter.eval_str("(fn square [x] (* x x))").unwrap();
+    stepper.step_in();
+    assert_eq!(interpreter.eval_str("(+ (square 2) 1)").unwrap().to_string(), "5");
+    assert_eq!(pauses(&stepper), vec![
+        "1:1 (+ (square 2) 1)",
+        "1:2 +",
+        "1:4 (square 2)",
+        "1:5 square",
+        "1:12 2",
+        "1:16 (* x x)",
+        //out of square's body, back in the caller
+        "1:15 1",
+    ]);
+}
+
+#[test]
+fn line_breakpoints(){
+    let (interpreter, stepper) = debugged(vec![Step::Continue; 3]);
+    stepper.add_breakpoint(Breakpoint::Line(2));
+    interpreter.eval_str("(fn count [n]\n  (if (> n 0)\n    (count (- n 1))\n    :done))\n(count 2)").unwrap();
+    //once per time the line is reached, not once per expression on it
+    assert_eq!(pauses(&stepper), vec!["2:3 (if (> n 0) (count (- n 1)) :done)"; 3]);
+    assert_eq!(*stepper.frontend().locals.borrow(), vec!["n=2", "n=1", "n=0"]);
+    assert!(stepper.remove_breakpoint(&Breakpoint::Line(2)));
+    assert!(stepper.breakpoints().is_empty());
+}
+
+#[test]
+fn quitting_aborts_the_evaluation(){
+    let (interpreter, stepper) = debugged(vec![]);
+    stepper.step_in();
+    let err = interpreter.eval_str("(+ 1 2)").unwrap_err();
+    assert!(matches!(err, InterpreterError::Eval(e) if matches!(e.error(), EvalError::Interrupted)));
+    stepper.reset();
+    assert_eq!(interpreter.eval_str("(+ 1 2)").unwrap().to_string(), "3");
+}
+
+//a host can implement the hooks directly
+#[derive(Default)]
+struct Calls{
+    names: RefCell<Vec<String>>,
+    depth: Cell<usize>,
+    deepest: Cell<usize>,
+}
+
+impl Debugger for Calls{
+    fn enter(&self, _scope: &ScopeRef, name: &str) {
+        self.depth.set(self.depth.get() + 1);
+        self.deepest.set(self.deepest.get().max(self.depth.get()));
+        self.names.borrow_mut().push(name.to_string());
+    }
+
+    fn leave(&self, _scope: &ScopeRef, _name: &str, _result: &EvalResult) {
+        self.depth.set(self.depth.get() - 1);
+    }
+}
+
+#[test]
+fn host_hooks(){
+    let interpreter = Interpreter::new();
+    let calls = Rc::new(Calls::default());
+    interpreter.set_debugger(Some(calls.clone()));
+    interpreter.eval_str("(fn inner [x] (* x 2)) (fn outer [xs] (map inner xs)) (outer (list 1 2))").unwrap();
+    assert_eq!(calls.depth.get(), 0);
+    //calls made by map go through the hooks too
+    assert_eq!(*calls.names.borrow(), vec!["outer", "inner", "inner"]);
+    assert_eq!(calls.deepest.get(), 2);
+    interpreter.set_debugger(None);
+}
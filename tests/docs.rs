@@ -0,0 +1,53 @@
+use kisp::assert_match;
+use kisp::interpreter::{Interpreter, InterpreterOptions};
+use kisp::stdlib::{std_lib_functions, StdModule};
+use kisp::testutils::text;
+use kisp::value::EvalValue;
+
+#[test]
+fn every_builtin_is_documented(){
+    for builtin in std_lib_functions() {
+        let doc = builtin.doc.unwrap_or_else(|| panic!("{} has no documentation", builtin.name));
+        let mut lines = doc.lines();
+        assert!(lines.next().unwrap().starts_with(&format!("({}", builtin.name)), "{} signature", builtin.name);
+        assert!(lines.next().is_some_and(|l| !l.is_empty()), "{} has no description", builtin.name);
+    }
+}
+
+#[test]
+fn documented_examples_hold(){
+    let mut checked = 0;
+    for builtin in std_lib_functions() {
+        for example in builtin.doc.unwrap_or_default().lines().filter_map(|l| l.strip_prefix("Example: ")) {
+            let Some((source, expected)) = example.split_once(" => ") else { continue };
+            let interpreter = Interpreter::with_options(InterpreterOptions{modules: StdModule::ALL.to_vec(), ..Default::default()});
+            let value = interpreter.eval_str(source).unwrap_or_else(|e| panic!("{}: {}", source, e));
+            assert_eq!(value.repr(), expected, "{}", source);
+            checked += 1;
+        }
+    }
+    assert!(checked > 20);
+}
+
+#[test]
+fn function_docstrings(){
+    let interpreter = Interpreter::new();
+    interpreter.eval_str("(fn square [x] \"Multiplies x by itself\" (* x x))").unwrap();
+    assert_eq!(text(&interpreter, "(square 4)"), "16");
+    assert_eq!(text(&interpreter, "(doc square)"), "(square [x])\nMultiplies x by itself");
+    assert_eq!(text(&interpreter, "(doc \"square\")"), "(square [x])\nMultiplies x by itself");
+    //a lone string is the body, not documentation
+    interpreter.eval_str("(fn greeting [] \"hello\")").unwrap();
+    assert_eq!(text(&interpreter, "(greeting)"), "hello");
+    assert_eq!(text(&interpreter, "(doc greeting)"), "(greeting [])");
+    assert!(interpreter.session_source().contains("(fn square [x] \"Multiplies x by itself\" (* x x))"));
+}
+
+#[test]
+fn doc_needs_a_function(){
+    let interpreter = Interpreter::new();
+    assert!(text(&interpreter, "(doc :map)").starts_with("(map f list)\n"));
+    assert!(interpreter.eval_str("(doc 1)").is_err());
+    assert!(interpreter.eval_str("(doc \"missing\")").is_err());
+    assert_match!(interpreter.eval_str("(help car)"), Ok(EvalValue::Unit));
+}
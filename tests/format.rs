@@ -0,0 +1,60 @@
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use kisp::assert_match;
+use kisp::interpreter::{Interpreter, InterpreterError};
+use kisp::testutils::{temp_path, text};
+use kisp::value::EvalValue;
+
+#[test]
+fn placeholders(){
+    let interpreter = Interpreter::new();
+    assert_eq!(text(&interpreter, "(format \"{} + {} = {}\" 1 2.5 \"x\")"), "1 + 2.5 = x");
+    assert_eq!(text(&interpreter, "(format \"{{{}}}\" :k)"), "{:k}");
+    assert_eq!(text(&interpreter, "(format \"{:.3}|{:.1}|{:.2}\" 3.14159 2 \"abc\")"), "3.142|2.0|ab");
+    assert_eq!(text(&interpreter, "(format \"{:x} {:X} {:o} {:b} {:x}\" 255 255 8 5 -16)"), "ff FF 10 101 -10");
+}
+
+#[test]
+fn padding(){
+    let interpreter = Interpreter::new();
+    assert_eq!(text(&interpreter, "(format \"[{:5}][{:5}]\" 42 \"ab\")"), "[   42][ab   ]");
+    assert_eq!(text(&interpreter, "(format \"[{:<5}][{:^6}][{:->4}]\" 42 \"ab\" 1)"), "[42   ][  ab  ][---1]");
+    assert_eq!(text(&interpreter, "(format \"{:06.2} {:05}\" -3.14159 42)"), "-03.14 00042");
+}
+
+#[test]
+fn bad_templates(){
+    let interpreter = Interpreter::new();
+    for source in ["(format \"{}\")", "(format \"{}\" 1 2)", "(format \"{\" 1)", "(format \"{:q}\" 1)", "(format \"{:x}\" 1.5)"] {
+        assert_match!(interpreter.eval_str(source), Err(InterpreterError::Eval(_)));
+    }
+    assert_match!(interpreter.eval_str("(write)"), Ok(EvalValue::Unit));
+}
+
+#[test]
+fn limits_precision_and_width(){
+    let interpreter = Interpreter::new();
+    for source in ["(format \"{:.99999999}\" 1.5)", "(format \"{:.70000}\" 1)", "(format \"{:>3000000000}\" 1)"] {
+        assert_match!(interpreter.eval_str(source), Err(InterpreterError::Eval(_)));
+    }
+    assert_eq!(text(&interpreter, "(format \"{:.65535}\" 1.5)").len(), 65537);
+}
+
+#[test]
+fn filters_stdin(){
+    let script = temp_path("filter", ".kisp");
+    std::fs::write(&script, "(fn number [line n] (if line [(write (format \"{:>2} {}\\n\" n line)) (number (read-line) (+ n 1))]))\n(number (read-line) 1)").unwrap();
+    let mut child = Command::new(env!("CARGO_BIN_EXE_kisp"))
+        .args(["run", "--allow", "input"])
+        .arg(&script)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(b"alpha\r\nbeta\n").unwrap();
+    let output = child.wait_with_output().unwrap();
+    std::fs::remove_file(script).unwrap();
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), " 1 alpha\n 2 beta\n");
+}
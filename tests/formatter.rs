@@ -0,0 +1,50 @@
+use kisp::formatter::{format_source, FormatOptions};
+
+fn formatted(input: &str) -> String {
+    format_source(input, &FormatOptions::default()).unwrap()
+}
+
+#[test]
+fn flat_when_it_fits(){
+    assert_eq!(formatted("(+   1\n   2)"), "(+ 1 2)\n");
+}
+
+#[test]
+fn function_layout(){
+    let output = format_source(
+        "(fn sum [n] [(fn iter [n acc] (if (>= 0 n) acc (iter (- n 1) (+ acc n)))) (iter n 0)])",
+        &FormatOptions{width: 40, indent: 4}
+    ).unwrap();
+    assert_eq!(output, "\
+(fn sum [n]
+    [
+        (fn iter [n acc]
+            (if (>= 0 n)
+                acc
+                (iter (- n 1) (+ acc n))))
+        (iter n 0)
+    ])
+");
+}
+
+#[test]
+fn keeps_comments(){
+    let output = formatted(";leading\n(let x 1) ;trailing\n\n\n(let y 2)");
+    assert_eq!(output, ";leading\n(let x 1) ;trailing\n\n(let y 2)\n");
+}
+
+#[test]
+fn idempotent(){
+    let input = "
+        (fn fact [n] ;factorial
+            (if (<= n 1) 1 (* n (fact (- n 1)))))
+        (print (map (lambda [x] (fact x)) (list 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16 17 18 19 20)))
+    ";
+    let once = formatted(input);
+    assert_eq!(formatted(&once), once);
+}
+
+#[test]
+fn unclosed(){
+    assert!(format_source("(+ 1 2", &FormatOptions::default()).is_err());
+}
@@ -0,0 +1,63 @@
+use std::path::PathBuf;
+
+use kisp::assert_match;
+use kisp::interpreter::{Interpreter, InterpreterError};
+use kisp::stdlib::StdModule;
+use kisp::testutils::{temp_path, text, with_modules};
+use kisp::value::EvalValue;
+use kisp::value::convert::IntoKisp;
+use kisp::value::error::EvalError;
+
+fn with_fs() -> Interpreter {
+    with_modules(&[StdModule::Filesystem])
+}
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = temp_path(name, "");
+    let _ = std::fs::remove_dir_all(&dir);
+    dir
+}
+
+#[test]
+fn write_append_and_read(){
+    let interpreter = with_fs();
+    let dir = temp_dir("rw");
+    interpreter.define("dir", dir.to_string_lossy().into_owned().into_kisp());
+    interpreter.eval_str("(mkdir dir) (let file (path-join dir \"report.txt\"))").unwrap();
+    interpreter.eval_str("(write-file file \"one\n\") (append-file file \"two\n\")").unwrap();
+    assert_eq!(text(&interpreter, "(read-file file)"), "one\ntwo\n");
+    assert_eq!(text(&interpreter, "(car (cdr (read-lines file)))"), "two");
+    assert_eq!(text(&interpreter, "(car (list-dir dir))"), "report.txt");
+
+    interpreter.eval_str("(remove-file file)").unwrap();
+    assert_match!(interpreter.eval_str("(file-exists? file)"), Ok(EvalValue::Unit));
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn path_helpers(){
+    let interpreter = with_fs();
+    //joined with the platform's separator
+    let joined = PathBuf::from("a").join("b").join("c.kisp").display().to_string();
+    assert_eq!(text(&interpreter, "(path-join \"a\" \"b\" \"c.kisp\")"), joined);
+    assert_eq!(text(&interpreter, "(basename \"a/b/c.kisp\")"), "c.kisp");
+    assert_eq!(text(&interpreter, "(extension \"a/b/c.kisp\")"), "kisp");
+    assert_match!(interpreter.eval_str("(extension \"a/b/c\")"), Ok(EvalValue::Unit));
+}
+
+#[test]
+fn os_errors_carry_the_message(){
+    let interpreter = with_fs();
+    let err = interpreter.eval_str("(read-file \"/definitely/not/here\")").unwrap_err();
+    assert_match!(err, InterpreterError::Eval(e) if matches!(e.error(), EvalError::Io(m) if m.starts_with("/definitely/not/here: ")));
+}
+
+#[test]
+fn try_catches_errors(){
+    let interpreter = with_fs();
+    assert_match!(interpreter.eval_str("(try (read-file \"/definitely/not/here\"))"), Ok(EvalValue::Unit));
+    let message = text(&interpreter, "(try (read-file \"/definitely/not/here\") (lambda [e] e))");
+    assert!(message.starts_with("/definitely/not/here: "));
+    assert_eq!(text(&interpreter, "(try (+ 1 2) (lambda [e] 0))"), "3");
+    assert_eq!(text(&interpreter, "(try (undefined-thing) (lambda [e] e))"), "unknown symbol `undefined-thing`");
+}
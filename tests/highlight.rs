@@ -0,0 +1,40 @@
+use kisp::highlight::highlight;
+
+fn strip(coloured: &str) -> String {
+    let mut out = String::new();
+    let mut chars = coloured.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            chars.by_ref().take_while(|c| *c != 'm').for_each(drop);
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+fn is_builtin(name: &str) -> bool {
+    ["print", "+"].contains(&name)
+}
+
+#[test]
+fn text_is_preserved(){
+    let source = "(fn f [x] ; comment (\n  (print \"a (b\" :k 1.5 x))";
+    assert_eq!(strip(&highlight(source, is_builtin)), source);
+}
+
+#[test]
+fn tokens_get_their_colours(){
+    assert_eq!(highlight("(+ 1)", is_builtin), "\x1b[33m(\x1b[0m\x1b[1;34m+\x1b[0m \x1b[36m1\x1b[0m\x1b[33m)\x1b[0m");
+    assert_eq!(highlight("x ; note", is_builtin), "x \x1b[90m; note\x1b[0m");
+    assert_eq!(highlight("\"s\" :k", is_builtin), "\x1b[32m\"s\"\x1b[0m \x1b[35m:k\x1b[0m");
+    //user names stay plain
+    assert_eq!(highlight("print-all", is_builtin), "print-all");
+}
+
+#[test]
+fn brackets_pair_by_depth(){
+    let coloured = highlight("([])", is_builtin);
+    assert_eq!(coloured, "\x1b[33m(\x1b[0m\x1b[35m[\x1b[0m\x1b[35m]\x1b[0m\x1b[33m)\x1b[0m");
+    assert_eq!(highlight(")", is_builtin), "\x1b[1;31m)\x1b[0m");
+}
@@ -0,0 +1,94 @@
+use std::cell::Cell;
+use std::rc::Rc;
+
+use kisp::assert_match;
+use kisp::interpreter::{Interpreter, InterpreterError, InterpreterOptions};
+use kisp::stdlib::StdModule;
+use kisp::value::builtin::BuiltinFunction;
+use kisp::value::EvalContext;
+use kisp::value::EvalValue;
+use kisp::value::error::EvalError;
+use kisp::value::numeric::Numeric;
+
+#[test]
+fn definitions_persist(){
+    let interpreter = Interpreter::new();
+    interpreter.eval_str("(fn double [x] (* x 2))").unwrap();
+    let value = interpreter.eval_str("(double 21)").unwrap();
+    assert_match!(value, EvalValue::Numeric(Numeric::Integer(42)));
+}
+
+#[test]
+fn define_get_and_call(){
+    let interpreter = Interpreter::new();
+    interpreter.define("limit", EvalValue::Numeric(Numeric::Integer(10)));
+    interpreter.eval_str("(fn clamp [x] (if (> x limit) limit x))").unwrap();
+    assert_match!(interpreter.get("limit"), Some(EvalValue::Numeric(Numeric::Integer(10))));
+    assert!(interpreter.get("missing").is_none());
+
+    let value = interpreter.call("clamp", vec![EvalValue::Numeric(Numeric::Integer(15))]).unwrap();
+    assert_match!(value, EvalValue::Numeric(Numeric::Integer(10)));
+    let value = interpreter.call("+", vec![EvalValue::Numeric(Numeric::Integer(1)), EvalValue::Numeric(Numeric::Integer(2))]).unwrap();
+    assert_match!(value, EvalValue::Numeric(Numeric::Integer(3)));
+    assert_match!(interpreter.call("limit", vec![]), Err(InterpreterError::Eval(_)));
+}
+
+#[test]
+fn stack_limit(){
+    let interpreter = Interpreter::with_options(InterpreterOptions{stack_limit: 20, ..Default::default()});
+    let err = interpreter.eval_str("(fn deep [n] (if (>= 0 n) 0 (+ 1 (deep (- n 1))))) (deep 50)").unwrap_err();
+    assert_match!(err, InterpreterError::Eval(e) if matches!(e.error(), EvalError::StackOverflow));
+    assert!(interpreter.eval_str("(deep 5)").is_ok());
+}
+
+#[test]
+fn error_display(){
+    let interpreter = Interpreter::new();
+    let err = interpreter.eval_str("(fn inner [x] (car x)) (fn outer [] (inner 1)) (outer)").unwrap_err();
+    assert_eq!(err.to_string(), "Eval: expected list, found int\n  #0 <function: inner>\n  #1 <function: outer>");
+}
+
+#[test]
+fn selected_modules(){
+    let interpreter = Interpreter::with_options(InterpreterOptions{
+        modules: vec![StdModule::Lang, StdModule::Arithmetic],
+        ..Default::default()
+    });
+    assert!(interpreter.eval_str("(+ 1 2)").is_ok());
+    let err = interpreter.eval_str("(list 1 2)").unwrap_err();
+    assert_match!(err, InterpreterError::Eval(e) if matches!(e.error(), EvalError::UnknownSymbol(s) if s == "list"));
+}
+
+#[test]
+fn errors(){
+    let mut interpreter = Interpreter::new();
+    assert_match!(interpreter.eval_str("(+ 1"), Err(InterpreterError::Parser(_)));
+    assert_match!(interpreter.eval_file("does/not/exist.kisp"), Err(InterpreterError::Io(_)));
+    interpreter.eval_str("(let x 1)").unwrap();
+    interpreter.reset();
+    assert!(interpreter.get("x").is_none());
+    assert!(interpreter.get("+").is_some());
+}
+
+#[test]
+fn register_closures(){
+    let mut interpreter = Interpreter::new();
+    let counter = Rc::new(Cell::new(0));
+    let captured = counter.clone();
+    interpreter.register_fn("tick", move |_scope, _ctx, _args| {
+        captured.set(captured.get() + 1);
+        Ok((EvalValue::Numeric(Numeric::Integer(captured.get())), EvalContext::none()))
+    });
+    for name in ["first", "second"] {
+        let reply = name.to_string();
+        interpreter.register(BuiltinFunction::new(format!("name-of-{}", name), move |_scope, _ctx, _args| {
+            Ok((EvalValue::Numeric(Numeric::Integer(reply.len() as i32)), EvalContext::none()))
+        }));
+    }
+
+    interpreter.eval_str("(tick) (tick)").unwrap();
+    assert_eq!(counter.get(), 2);
+    assert_match!(interpreter.eval_str("(name-of-second)").unwrap(), EvalValue::Numeric(Numeric::Integer(6)));
+    interpreter.reset();
+    assert_match!(interpreter.eval_str("(tick)").unwrap(), EvalValue::Numeric(Numeric::Integer(3)));
+}
@@ -0,0 +1,77 @@
+use kisp::assert_match;
+use kisp::interpreter::{Interpreter, InterpreterError};
+use kisp::testutils::text;
+use kisp::value::EvalValue;
+use kisp::value::error::EvalError;
+use kisp::value::numeric::Numeric;
+
+#[test]
+fn parse_maps_values(){
+    let interpreter = Interpreter::new();
+    interpreter.eval_str("(let doc (json-parse \"{\\\"name\\\": \\\"kisp\\\", \\\"tags\\\": [1, 2.5, true, false, null]}\"))").unwrap();
+    assert_eq!(text(&interpreter, "(car (car doc))"), ":name");
+    assert_eq!(text(&interpreter, "(car (cdr (car doc)))"), "kisp");
+    let tags = interpreter.eval_str("(car (cdr (car (cdr doc))))").unwrap();
+    let values: Vec<EvalValue> = kisp::value::convert::FromKisp::from_kisp(&tags).unwrap();
+    assert_match!(values[0], EvalValue::Numeric(Numeric::Integer(1)));
+    assert_match!(values[1], EvalValue::Numeric(Numeric::Floating(f)) if f == 2.5);
+    assert_match!(values[2], EvalValue::True);
+    assert_match!(values[3], EvalValue::Unit);
+    assert_eq!(values[4].to_string(), ":null");
+}
+
+#[test]
+fn stringify_round_trip(){
+    let interpreter = Interpreter::new();
+    let source = "{\"a\":[1,2.5,\"x\\ny\"],\"b\":null,\"c\":{\"d\":true}}";
+    interpreter.define("source", kisp::value::convert::IntoKisp::into_kisp(source));
+    assert_eq!(text(&interpreter, "(json-stringify (json-parse source))"), source);
+    assert_eq!(text(&interpreter, "(json-stringify (list (list :id 7) (list :tags (list))))"), "{\"id\":7,\"tags\":[]}");
+    assert_eq!(text(&interpreter, "(json-stringify (list 1 :two \"three\"))"), "[1,\"two\",\"three\"]");
+    //false, null, [] and {} stay apart, arrays of pairs with string keys stay arrays
+    for source in ["[false,null,[],{}]", "{\"a\":{},\"b\":false}", "[[\"a\",1]]", "[{\"a\":1}]"] {
+        interpreter.define("source", kisp::value::convert::IntoKisp::into_kisp(source));
+        assert_eq!(text(&interpreter, "(json-stringify (json-parse source))"), source);
+    }
+    assert_eq!(text(&interpreter, "(json-parse \"{}\")"), ":empty-object");
+}
+
+#[test]
+fn pretty_output(){
+    let interpreter = Interpreter::new();
+    assert_eq!(
+        text(&interpreter, "(json-stringify (list (list :a (list 1 2)) (list :b (list))) :pretty true)"),
+        "{\n  \"a\": [\n    1,\n    2\n  ],\n  \"b\": []\n}"
+    );
+    assert_eq!(text(&interpreter, "(json-stringify (list 1) :pretty 4)"), "[\n    1\n]");
+}
+
+#[test]
+fn errors_carry_position(){
+    let interpreter = Interpreter::new();
+    let err = interpreter.eval_str("(json-parse \"[1,\n  2,,]\")").unwrap_err();
+    assert_match!(err, InterpreterError::Eval(e) if matches!(e.error(), EvalError::Json(j) if j.line == 2));
+    let message = text(&interpreter, "(try (json-parse \"{\") (lambda [e] e))");
+    assert!(message.starts_with("invalid json: "), "{}", message);
+    assert_match!(interpreter.eval_str("(json-stringify (lambda [x] x))"), Err(InterpreterError::Eval(_)));
+}
+
+#[test]
+fn rejects_deep_nesting_and_non_finite_numbers(){
+    let interpreter = Interpreter::new();
+    let err = interpreter.eval_str("(json-parse (format \"{:[>200000}\" \"\"))").unwrap_err();
+    assert_match!(err, InterpreterError::Eval(e) if matches!(e.error(), EvalError::Json(j) if j.message.contains("nested")));
+    assert_eq!(text(&interpreter, "(json-stringify (json-parse (format \"{:[>100}{:]>100}\" \"\" \"\")))").len(), 200);
+    assert_match!(interpreter.eval_str("(json-parse \"1e999\")"), Err(InterpreterError::Eval(_)));
+    assert_match!(interpreter.eval_str("(json-parse \"[-1e999]\")"), Err(InterpreterError::Eval(_)));
+}
+
+#[test]
+fn unicode_escapes(){
+    let interpreter = Interpreter::new();
+    assert_eq!(text(&interpreter, "(json-parse \"\\\"\\\\u00e9\\\\ud83d\\\\ude00\\\"\")"), "\u{e9}\u{1F600}");
+    for escape in ["\\\\ud83d\\\\u0041", "\\\\udc00", "\\\\ud83d"] {
+        let source = format!("(json-parse \"\\\"{}\\\"\")", escape);
+        assert_match!(interpreter.eval_str(&source), Err(InterpreterError::Eval(e)) if matches!(e.error(), EvalError::Json(_)));
+    }
+}
@@ -0,0 +1,44 @@
+use kisp::assert_match;
+use kisp::testutils::quick_result;
+use kisp::value::EvalValue;
+use kisp::value::error::EvalError;
+use kisp::value::numeric::Numeric;
+
+#[test]
+fn keyword_literal(){
+    let (value, _) = quick_result("(list :init (is-keyword? :init) (is-keyword? 1))").unwrap();
+    assert_eq!(value.to_string(), "<list: :init true unit>");
+}
+
+#[test]
+fn keyword_parameters(){
+    let (value, _) = quick_result("
+        (fn range [from :to 10 :step 1] (list from to step))
+        (list (range 0) (range 0 :step 2) (range 0 :step 3 :to 5))
+    ").unwrap();
+    assert_eq!(value.to_string(), "<list: <list: 0 10 1> <list: 0 10 2> <list: 0 5 3>>");
+}
+
+#[test]
+fn keyword_defaults_see_earlier_parameters(){
+    let (value, _) = quick_result("(fn pad [x :left x :right left] (+ x left right)) (pad 1 :left 2)").unwrap();
+    assert_match!(value, EvalValue::Numeric(Numeric::Integer(5)));
+}
+
+#[test]
+fn keywords_do_not_count_towards_arity(){
+    let err = quick_result("(fn f [a :b 1] a) (f :b 2)").unwrap_err();
+    assert_match!(err.error(), EvalError::ArityMismatch{min: 1, max: Some(1), actual: 0, ..});
+}
+
+#[test]
+fn builtin_named_arguments(){
+    let (value, _) = quick_result("
+        (list
+            (fold (lambda [acc x] (+ acc x)) (list 1 2 3) :init 10)
+            (fold 10 (lambda [acc x] (+ acc x)) (list 1 2 3))
+            (nth (list 4 5 6) :index 1)
+            (nth 1 (list 4 5 6)))
+    ").unwrap();
+    assert_eq!(value.to_string(), "<list: 16 16 5 5>");
+}
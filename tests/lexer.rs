@@ -0,0 +1,25 @@
+use kisp::lexer::open_depth;
+
+#[test]
+fn balanced_input_is_complete(){
+    assert_eq!(open_depth(""), 0);
+    assert_eq!(open_depth("(+ 1 2)"), 0);
+    assert_eq!(open_depth("(fn f [x]\n  (* x 2))\n"), 0);
+    //stray closers are the parser's problem, not a reason to keep reading
+    assert_eq!(open_depth("(+ 1 2))"), 0);
+}
+
+#[test]
+fn open_brackets_are_counted(){
+    assert_eq!(open_depth("(fn f [x"), 2);
+    assert_eq!(open_depth("(fn f [x]\n  (* x"), 2);
+    assert_eq!(open_depth("[(list 1"), 2);
+}
+
+#[test]
+fn strings_and_comments_are_ignored(){
+    assert_eq!(open_depth("(print \"(((\")"), 0);
+    assert_eq!(open_depth("(print 1) ; (((\n"), 0);
+    assert_eq!(open_depth("(f ; )\n"), 1);
+    assert_eq!(open_depth("(print \"unfinished ("), 2);
+}
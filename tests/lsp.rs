@@ -0,0 +1,111 @@
+use kisp::json;
+use kisp::json::JsonValue;
+use kisp::lsp::{read_message, write_message, Server};
+
+const URI: &str = "file:///test.kisp";
+const SOURCE: &str = "(fn square [x] (* x x))\n(fn twice [f v] (f (f v)))\n(twice square 3)";
+
+fn request(id: usize, method: &str, params: &str) -> JsonValue {
+    json::parse(&format!(r#"{{"jsonrpc":"2.0","id":{},"method":"{}","params":{}}}"#, id, method, params)).unwrap()
+}
+
+fn notification(method: &str, params: &str) -> JsonValue {
+    json::parse(&format!(r#"{{"jsonrpc":"2.0","method":"{}","params":{}}}"#, method, params)).unwrap()
+}
+
+fn open(text: &str) -> JsonValue {
+    let document = JsonValue::object(vec![
+        ("uri", JsonValue::string(URI)),
+        ("languageId", JsonValue::string("kisp")),
+        ("version", JsonValue::Number(1.0)),
+        ("text", JsonValue::string(text)),
+    ]);
+    notification("textDocument/didOpen", &JsonValue::object(vec![("textDocument", document)]).to_string())
+}
+
+fn at(line: usize, character: usize) -> String {
+    format!(r#"{{"textDocument":{{"uri":"{}"}},"position":{{"line":{},"character":{}}}}}"#, URI, line, character)
+}
+
+//plays a whole session through the framed transport and collects everything the server sent
+fn session(messages: Vec<JsonValue>) -> Vec<JsonValue> {
+    let mut input: Vec<u8> = Vec::new();
+    for m in &messages {
+        write_message(&mut input, m).unwrap();
+    }
+    let mut output: Vec<u8> = Vec::new();
+    let mut server = Server::new();
+    server.run(&mut input.as_slice(), &mut output).unwrap();
+    let mut replies = Vec::new();
+    let mut reader = output.as_slice();
+    while let Some(reply) = read_message(&mut reader).unwrap() {
+        replies.push(reply);
+    }
+    replies
+}
+
+fn response(replies: &[JsonValue], id: f64) -> &JsonValue {
+    replies.iter()
+        .find(|r| r.get("id") == Some(&JsonValue::Number(id)))
+        .and_then(|r| r.get("result"))
+        .unwrap()
+}
+
+#[test]
+fn lifecycle(){
+    let replies = session(vec![
+        request(1, "initialize", "{}"),
+        notification("initialized", "{}"),
+        request(2, "shutdown", "null"),
+        notification("exit", "null"),
+        request(3, "initialize", "{}"),
+    ]);
+    assert_eq!(replies.len(), 2);
+    let capabilities = response(&replies, 1.0).get("capabilities").unwrap();
+    assert_eq!(capabilities.get("hoverProvider"), Some(&JsonValue::Bool(true)));
+    assert_eq!(response(&replies, 2.0), &JsonValue::Null);
+}
+
+#[test]
+fn diagnostics(){
+    let replies = session(vec![open("(+ 1 2)\n(print (+ 1 2)")]);
+    let params = replies[0].get("params").unwrap();
+    let JsonValue::Array(diagnostics) = params.get("diagnostics").unwrap() else { panic!() };
+    assert_eq!(diagnostics.len(), 1);
+    let start = diagnostics[0].get("range").unwrap().get("start").unwrap();
+    assert_eq!(start.get("line"), Some(&JsonValue::Number(1.0)));
+    assert_eq!(start.get("character"), Some(&JsonValue::Number(0.0)));
+}
+
+#[test]
+fn completion_and_hover(){
+    let replies = session(vec![
+        open(SOURCE),
+        request(1, "textDocument/completion", &at(0, 0)),
+        request(2, "textDocument/hover", &at(0, 16)),
+        request(3, "textDocument/hover", &at(2, 3)),
+    ]);
+    let JsonValue::Array(items) = response(&replies, 1.0) else { panic!() };
+    assert!(items.iter().any(|i| i.get("label").and_then(|l| l.as_str()) == Some("fold")));
+    let builtin = response(&replies, 2.0).get("contents").unwrap().get("value").unwrap().as_str().unwrap();
+    assert!(builtin.contains("builtin"));
+    let user = response(&replies, 3.0).get("contents").unwrap().get("value").unwrap().as_str().unwrap();
+    assert_eq!(user, "(fn twice [f v])");
+}
+
+#[test]
+fn definition_and_symbols(){
+    let replies = session(vec![
+        open(SOURCE),
+        request(1, "textDocument/definition", &at(2, 8)),
+        request(2, "textDocument/definition", &at(1, 22)),
+        request(3, "textDocument/documentSymbol", &format!(r#"{{"textDocument":{{"uri":"{}"}}}}"#, URI)),
+    ]);
+    let square = response(&replies, 1.0).get("range").unwrap().get("start").unwrap();
+    assert_eq!(square, &json::parse(r#"{"line":0,"character":4}"#).unwrap());
+    let param = response(&replies, 2.0).get("range").unwrap().get("start").unwrap();
+    assert_eq!(param, &json::parse(r#"{"line":1,"character":13}"#).unwrap());
+    let JsonValue::Array(symbols) = response(&replies, 3.0) else { panic!() };
+    let names: Vec<&str> = symbols.iter().filter_map(|s| s.get("name")).filter_map(|n| n.as_str()).collect();
+    assert_eq!(names, vec!["square", "twice"]);
+}
@@ -0,0 +1,74 @@
+use kisp::assert_match;
+use kisp::interpreter::{Interpreter, InterpreterError};
+use kisp::scope::ScopeRef;
+use kisp::value::EvalValue;
+use kisp::value::error::{ErrorContext, EvalError};
+use kisp::value::numeric::Numeric;
+
+/// Number of characters in a string
+#[kisp::builtin(name = "str-len")]
+fn str_len(s: String) -> i32 {
+    s.chars().count() as i32
+}
+
+/// Repeats a string, once by default
+#[kisp::builtin]
+fn repeat_string(s: String, times: Option<i32>) -> String {
+    s.repeat(times.unwrap_or(1) as usize)
+}
+
+#[kisp::builtin(name = "checked-div", variadic)]
+fn checked_div(scope: &ScopeRef, first: f64, rest: Vec<f64>) -> Result<f64, ErrorContext> {
+    rest.into_iter().try_fold(first, |acc, d| match d {
+        0.0 => Err(EvalError::Other("division by zero".to_string()).trace(scope)),
+        d => Ok(acc / d),
+    })
+}
+
+fn interpreter() -> Interpreter {
+    let interpreter = Interpreter::new();
+    interpreter.register(str_len_builtin());
+    interpreter.register(repeat_string_builtin());
+    interpreter.register(checked_div_builtin());
+    interpreter
+}
+
+#[test]
+fn generated_builtins(){
+    let interpreter = interpreter();
+    assert_match!(interpreter.eval_str("(str-len \"héllo\")").unwrap(), EvalValue::Numeric(Numeric::Integer(5)));
+    assert_eq!(interpreter.eval_str("(list (repeat-string \"ab\") (repeat-string \"ab\" 3))").unwrap().to_string(), "<list: ab ababab>");
+    assert_match!(interpreter.eval_str("(checked-div 12 2 3)").unwrap(), EvalValue::Numeric(Numeric::Floating(f)) if f == 2.0);
+}
+
+#[test]
+fn generated_checks(){
+    let interpreter = interpreter();
+    let err = interpreter.eval_str("(str-len)").unwrap_err();
+    assert_match!(err, InterpreterError::Eval(e) if matches!(e.error(), EvalError::ArityMismatch{min: 1, max: Some(1), actual: 0, ..}));
+    let err = interpreter.eval_str("(repeat-string \"a\" 1 2)").unwrap_err();
+    assert_match!(err, InterpreterError::Eval(e) if matches!(e.error(), EvalError::ArityMismatch{min: 1, max: Some(2), actual: 3, ..}));
+    let err = interpreter.eval_str("(str-len 1)").unwrap_err();
+    assert_match!(err, InterpreterError::Eval(e) if matches!(e.error(), EvalError::TypeMismatch{found: "int", ..}));
+    let err = interpreter.eval_str("(checked-div 1 0)").unwrap_err();
+    assert_match!(err, InterpreterError::Eval(e) if matches!(e.error(), EvalError::Other(_)));
+}
+
+#[test]
+fn generated_docs(){
+    assert_eq!(str_len_builtin().doc.as_deref(), Some("(str-len s)\nNumber of characters in a string"));
+    assert_eq!(checked_div_builtin().doc.as_deref(), Some("(checked-div first rest...)"));
+    let plus = kisp::stdlib::std_lib_functions().into_iter().find(|b| b.name == "+").unwrap();
+    assert_eq!(plus.doc.as_deref(), Some("(+ first rest...)\nSum of all arguments\nExample: (+ 1 2 3) => 6"));
+}
+
+#[test]
+fn migrated_type_builtins(){
+    let interpreter = Interpreter::new();
+    //float used to be registered with the int callback
+    assert_match!(interpreter.eval_str("(float 1)"), Ok(EvalValue::Numeric(Numeric::Floating(f))) if f == 1.0);
+    assert_match!(interpreter.eval_str("(int 2.7)"), Ok(EvalValue::Numeric(Numeric::Integer(2))));
+    assert_match!(interpreter.eval_str("(is-list? (list))"), Ok(EvalValue::True));
+    assert_match!(interpreter.eval_str("(is_list? (list 1))"), Ok(EvalValue::True));
+    assert_match!(interpreter.eval_str("(is-list? ())"), Ok(EvalValue::Unit));
+}
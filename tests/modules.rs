@@ -0,0 +1,67 @@
+use kisp::assert_match;
+use kisp::interpreter::{env_scope, Interpreter, InterpreterError, InterpreterOptions};
+use kisp::stdlib::StdModule;
+use kisp::testutils::{run_script, with_modules};
+use kisp::value::EvalValue;
+use kisp::value::error::EvalError;
+
+fn unknown(result: Result<EvalValue, InterpreterError>) -> String {
+    match result {
+        Err(InterpreterError::Eval(e)) => match e.error() {
+            EvalError::UnknownSymbol(s) => s.clone(),
+            other => panic!("unexpected error {:?}", other),
+        },
+        _ => panic!("expected an unknown symbol"),
+    }
+}
+
+#[test]
+fn side_effects_are_opt_in(){
+    let interpreter = Interpreter::new();
+    assert_eq!(unknown(interpreter.eval_str("(read-file \"Cargo.toml\")")), "read-file");
+    assert_eq!(unknown(interpreter.eval_str("(getenv \"HOME\")")), "getenv");
+    assert_eq!(unknown(interpreter.eval_str("(now)")), "now");
+    assert!(interpreter.get("print").is_some());
+}
+
+#[test]
+fn pure_interpreter_lacks_output(){
+    let interpreter = Interpreter::with_options(InterpreterOptions{modules: StdModule::PURE.to_vec(), ..Default::default()});
+    assert_eq!(unknown(interpreter.eval_str("(print 1)")), "print");
+    assert_match!(interpreter.eval_str("(+ 1 (car (list 2 3)))"), Ok(EvalValue::Numeric(_)));
+}
+
+#[test]
+fn enabled_modules_are_installed(){
+    let interpreter = with_modules(&[StdModule::Filesystem, StdModule::Time]);
+    assert_match!(interpreter.eval_str("(file-exists? \"Cargo.toml\")"), Ok(EvalValue::True));
+    assert_match!(interpreter.eval_str("(> (now) 0)"), Ok(EvalValue::True));
+    assert_match!(interpreter.eval_str("(sleep 1)"), Ok(EvalValue::Unit));
+    for millis in ["1e300", "(/ 1.0 0.0)"] {
+        assert_match!(interpreter.eval_str(&format!("(sleep {})", millis)), Err(InterpreterError::Eval(_)));
+    }
+    assert_eq!(unknown(interpreter.eval_str("(exit 1)")), "exit");
+}
+
+#[test]
+fn module_names(){
+    for module in StdModule::ALL {
+        assert_eq!(StdModule::from_name(module.name()), Some(module));
+    }
+    assert_eq!(StdModule::from_name("network"), None);
+    let scope = env_scope(&[StdModule::Arithmetic]);
+    assert!(scope.lookup(&"+".to_string()).is_some());
+    assert!(scope.lookup(&"print".to_string()).is_none());
+}
+
+#[test]
+fn exit_reaches_the_host(){
+    let interpreter = Interpreter::with_options(InterpreterOptions{modules: vec![StdModule::Lang, StdModule::Process], ..Default::default()});
+    let err = interpreter.eval_str("(try (exit 3) (lambda [e] 0))").unwrap_err();
+    assert_eq!(err.exit_code(), Some(3));
+    assert_match!(err, InterpreterError::Eval(e) if matches!(e.error(), EvalError::Exit(3)));
+
+    let output = run_script(env!("CARGO_BIN_EXE_kisp"), "exit", &["--allow", "process"], "(exit 3)");
+    assert_eq!(output.status.code(), Some(3));
+    assert!(output.stderr.is_empty());
+}
@@ -0,0 +1,67 @@
+use std::cell::Cell;
+use std::rc::Rc;
+
+use kisp::assert_match;
+use kisp::interpreter::{Interpreter, InterpreterError};
+use kisp::value::builtin::BuiltinFunction;
+use kisp::value::native::NativeType;
+use kisp::value::{EvalContext, EvalValue};
+use kisp::value::error::EvalError;
+use kisp::value::numeric::Numeric;
+
+struct Counter{
+    count: Cell<i32>,
+}
+
+fn counter_type() -> Rc<NativeType> {
+    Rc::new(
+        NativeType::new("Counter")
+            .with_method(BuiltinFunction::wrap("add", |c: Rc<Counter>, n: i32| {
+                c.count.set(c.count.get() + n);
+                c.count.get()
+            }))
+            .with_method(BuiltinFunction::new("get", |scope, _ctx, args| {
+                let counter = args.try_pos(scope, 0)?.native::<Counter>(scope)?;
+                Ok((EvalValue::Numeric(Numeric::Integer(counter.count.get())), EvalContext::none()))
+            }))
+    )
+}
+
+#[test]
+fn methods_and_host_access(){
+    let interpreter = Interpreter::new();
+    let kind = counter_type();
+    interpreter.define("counter", EvalValue::native(&kind, Counter{count: Cell::new(1)}));
+    let value = interpreter.eval_str("(invoke counter :add 2) (invoke counter \"add\" 3) (invoke counter :get)").unwrap();
+    assert_match!(value, EvalValue::Numeric(Numeric::Integer(6)));
+
+    let EvalValue::Reference(r) = interpreter.get("counter").unwrap() else { panic!("not a reference") };
+    let kisp::value::ReferenceValue::Native(object) = r.as_ref() else { panic!("not native") };
+    assert_eq!(object.downcast::<Counter>().unwrap().count.get(), 6);
+    assert!(object.downcast::<String>().is_none());
+}
+
+#[test]
+fn type_names(){
+    let interpreter = Interpreter::new();
+    interpreter.define("counter", EvalValue::native(&counter_type(), Counter{count: Cell::new(0)}));
+    let value = interpreter.eval_str(
+        r#"(list counter (type-name counter) (type-name 1) (is-native? counter) (is-native? counter "Counter") (is-native? counter "File") (is-native? 1))"#
+    ).unwrap();
+    assert_eq!(value.to_string(), "<list: <native: Counter> Counter int true true unit unit>");
+}
+
+#[test]
+fn invoke_errors(){
+    let interpreter = Interpreter::new();
+    interpreter.define("counter", EvalValue::native(&counter_type(), Counter{count: Cell::new(0)}));
+    interpreter.define("other", EvalValue::native(&Rc::new(NativeType::new("Other").with_method(
+        BuiltinFunction::wrap("poke", |c: Rc<Counter>| c.count.get())
+    )), 5));
+
+    let err = interpreter.eval_str("(invoke counter :reset)").unwrap_err();
+    assert_match!(err, InterpreterError::Eval(e) if matches!(e.error(), EvalError::UnknownMethod{method, ..} if method == "reset"));
+    let err = interpreter.eval_str("(invoke other :poke)").unwrap_err();
+    assert_match!(err, InterpreterError::Eval(e) if matches!(e.error(), EvalError::TypeMismatch{found: "native", ..}));
+    assert!(interpreter.eval_str("(invoke 1 :get)").is_err());
+}
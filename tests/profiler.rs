@@ -0,0 +1,81 @@
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use kisp::interpreter::Interpreter;
+use kisp::profiler::{FunctionProfile, Profiler};
+use kisp::testutils::{run_script, temp_path};
+
+const SOURCE: &str = "\
+(fn square [x] (* x x))
+(fn fact [n] (if (= n 0) 1 (* n (fact (- n 1)))))
+(fn sum [xs] (fold 0 (lambda [a b] (+ a (square b))) xs))
+(fn pair [x] (list x x))
+(fn pairs [xs] (map pair xs))
+(sum (list 1 2 3))
+(fact 4)
+(pairs (list 1 2))";
+
+fn profiled(source: &str) -> (Rc<Profiler>, HashMap<String, FunctionProfile>) {
+    let interpreter = Interpreter::new();
+    let profiler = Rc::new(Profiler::new());
+    interpreter.set_profiler(Some(profiler.clone()));
+    interpreter.eval_str(source).unwrap();
+    let functions = profiler.functions().into_iter().collect();
+    (profiler, functions)
+}
+
+#[test]
+fn counts_calls_per_function_and_lambda(){
+    let (_, functions) = profiled(SOURCE);
+    let calls: HashMap<&str, u64> = functions.iter().map(|(label, p)| (label.as_str(), p.calls)).collect();
+    assert_eq!(calls, HashMap::from([
+        ("square", 3), ("fact", 5), ("sum", 1), ("lambda@3:36", 3), ("pair", 2), ("pairs", 1),
+    ]));
+    for (label, p) in &functions {
+        assert!(p.exclusive <= p.inclusive, "{}", label);
+    }
+    //callers include the time of what they call
+    assert!(functions["sum"].inclusive >= functions["lambda@3:36"].inclusive);
+    assert!(functions["lambda@3:36"].inclusive >= functions["square"].inclusive);
+}
+
+#[test]
+fn counts_list_cells(){
+    let (_, functions) = profiled(SOURCE);
+    assert_eq!((functions["pair"].inclusive_cells, functions["pair"].exclusive_cells), (4, 4));
+    //the result list is built by map itself, which belongs to pairs
+    assert_eq!((functions["pairs"].inclusive_cells, functions["pairs"].exclusive_cells), (6, 2));
+    assert_eq!(functions["square"].inclusive_cells, 0);
+}
+
+#[test]
+fn folded_stacks(){
+    let (profiler, _) = profiled(SOURCE);
+    let folded = profiler.folded();
+    let stacks: Vec<&str> = folded.lines()
+        .map(|line| {
+            let (stack, micros) = line.rsplit_once(' ').unwrap();
+            assert!(micros.parse::<u128>().is_ok(), "{}", line);
+            stack
+        })
+        .collect();
+    assert!(stacks.contains(&"sum;lambda@3:36;square"));
+    assert!(stacks.contains(&"fact;fact;fact;fact;fact"));
+    assert!(stacks.contains(&"pairs;pair"));
+    assert!(profiler.report().starts_with("function"));
+    profiler.clear();
+    assert!(profiler.functions().is_empty());
+}
+
+#[test]
+fn profile_flags(){
+    let folded = temp_path("profile", ".folded");
+    let output = run_script(env!("CARGO_BIN_EXE_kisp"), "profile", &["--profile", "--folded", &folded.to_string_lossy()], SOURCE);
+    let stacks = std::fs::read_to_string(&folded).unwrap();
+    std::fs::remove_file(folded).unwrap();
+    assert!(output.status.success());
+    let report = String::from_utf8(output.stderr).unwrap();
+    assert!(report.lines().next().unwrap().starts_with("function"));
+    assert!(report.lines().any(|l| l.starts_with("fact ")));
+    assert!(stacks.lines().any(|l| l.starts_with("pairs;pair ")));
+}
@@ -0,0 +1,70 @@
+use proptest::prelude::*;
+
+use kisp::assert_match;
+use kisp::interpreter::Interpreter;
+use kisp::value::list::List;
+use kisp::value::numeric::Numeric;
+use kisp::value::repr::read_value;
+use kisp::value::{EvalValue, ReferenceValue};
+
+fn reference(value: ReferenceValue) -> EvalValue {
+    EvalValue::Reference(value.to_rc())
+}
+
+fn data_value() -> impl Strategy<Value = EvalValue> {
+    let leaf = prop_oneof![
+        Just(EvalValue::Unit),
+        Just(EvalValue::True),
+        any::<i32>().prop_map(|i| EvalValue::Numeric(Numeric::Integer(i))),
+        any::<f64>().prop_filter("nan never equals itself", |f| !f.is_nan())
+            .prop_map(|f| EvalValue::Numeric(Numeric::Floating(f))),
+        any::<String>().prop_map(|s| reference(ReferenceValue::String(s))),
+        "[a-z][a-z0-9?!-]{0,8}".prop_map(|k| reference(ReferenceValue::Keyword(k))),
+    ];
+    leaf.prop_recursive(4, 32, 6, |inner|
+        prop::collection::vec(inner, 0..6).prop_map(|values| reference(ReferenceValue::List(List::from(values))))
+    )
+}
+
+proptest! {
+    #[test]
+    fn read_inverts_repr(value in data_value()) {
+        let printed = value.repr();
+        let read = read_value(&printed).unwrap();
+        prop_assert_eq!(read.type_name(), value.type_name(), "{}", printed);
+        prop_assert!(read == value, "{} read back as {}", printed, read.repr());
+    }
+}
+
+#[test]
+fn repr_syntax(){
+    let value = reference(ReferenceValue::List(List::from(vec![
+        EvalValue::Numeric(Numeric::Integer(1)),
+        EvalValue::Numeric(Numeric::Floating(2.0)),
+        reference(ReferenceValue::String("a \"b\"\n".to_string())),
+        reference(ReferenceValue::Keyword("k".to_string())),
+        EvalValue::Unit,
+        reference(ReferenceValue::List(List::from(vec![]))),
+    ])));
+    assert_eq!(value.repr(), "(list 1 2.0 \"a \\\"b\\\"\\n\" :k () (list))");
+}
+
+#[test]
+fn builtins(){
+    let interpreter = Interpreter::new();
+    assert_match!(interpreter.eval_str("(= (read (repr (list 1 \"x\" :y))) (list 1 \"x\" :y))"), Ok(EvalValue::True));
+    assert_match!(interpreter.eval_str("(!= (list 1 2) (list 1 2 3))"), Ok(EvalValue::True));
+    assert_match!(interpreter.eval_str("(eval (read \"(+ 1 2)\"))"), Ok(EvalValue::Numeric(Numeric::Integer(3))));
+    assert_eq!(interpreter.eval_str("(repr (quote (f :a \"s\")))").unwrap().to_string(), "(quote (f :a \"s\"))");
+    assert!(interpreter.eval_str("(read \"1 2\")").is_err());
+    assert!(interpreter.eval_str("(read \"(list 1\")").is_err());
+}
+
+#[test]
+fn rejects_deep_nesting(){
+    let interpreter = Interpreter::new();
+    assert!(interpreter.eval_str("(read (format \"{:(>200000}\" \"\"))").is_err());
+    assert!(interpreter.eval_str("(read (format \"{:[>200000}\" \"\"))").is_err());
+    let nested = format!("{}{}", "(list ".repeat(100), ")".repeat(100));
+    assert!(read_value(&nested).is_ok());
+}
@@ -0,0 +1,88 @@
+use kisp::resolver::{check_source, DiagnosticKind, Severity};
+
+fn kinds(source: &str) -> Vec<DiagnosticKind> {
+    check_source(source).unwrap().into_iter().map(|d| d.kind).collect()
+}
+
+#[test]
+fn clean_program(){
+    assert!(kinds("
+        (fn sum [n]
+            [
+                (fn iter [n acc]
+                    (if (>= 0 n) acc (iter (- n 1) (+ acc n))))
+                (iter n 0)
+            ])
+        (let total (sum 100))
+        (print (map (lambda [x] (* x total)) (list 1 2 3)))
+    ").is_empty());
+}
+
+#[test]
+fn unbound_in_untaken_branch(){
+    let diagnostics = check_source("(if true 1 (pritn 2))").unwrap();
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].kind, DiagnosticKind::UnboundSymbol("pritn".to_string()));
+    assert_eq!(diagnostics[0].severity(), Severity::Error);
+    assert_eq!((diagnostics[0].cursor.line(), diagnostics[0].cursor.column()), (1, 13));
+}
+
+#[test]
+fn let_binds_after_value(){
+    assert_eq!(kinds("(let x (+ x 1))"), vec![DiagnosticKind::UnboundSymbol("x".to_string())]);
+    assert!(kinds("(let x 1) (+ x 1) (quote (anything goes))").is_empty());
+}
+
+#[test]
+fn shadowed_and_unused(){
+    assert_eq!(
+        kinds("(fn f [list unused _ignored] (car list)) (f 1 2 3)"),
+        vec![
+            DiagnosticKind::ShadowedBuiltin("list".to_string()),
+            DiagnosticKind::UnusedBinding("unused".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn wrong_arity(){
+    assert_eq!(
+        kinds("(fn add [a b] (+ a b)) (add 1)"),
+        vec![DiagnosticKind::WrongArity{name: "add".to_string(), min: 2, max: Some(2), actual: 1}]
+    );
+}
+
+#[test]
+fn hoisted_functions(){
+    assert!(kinds("(fn even [n] (if (= n 0) true (odd (- n 1)))) (fn odd [n] (if (= n 0) () (even (- n 1)))) (even 4)").is_empty());
+}
+
+#[test]
+fn variadic_arity(){
+    let source = "(fn f [a (b a) & more] (list a b more)) (f 1 2 3 4) (f)";
+    assert_eq!(
+        kinds(source),
+        vec![DiagnosticKind::WrongArity{name: "f".to_string(), min: 1, max: None, actual: 0}]
+    );
+}
+
+#[test]
+fn used_before_fn_form(){
+    assert_eq!(kinds("(f 1) (fn f [x] x)"), vec![DiagnosticKind::UsedBeforeDefinition("f".to_string())]);
+    //g itself is defined, but calling it needs h
+    assert_eq!(kinds("(fn g [] (h)) (g) (fn h [] 1)"), vec![DiagnosticKind::UsedBeforeDefinition("h".to_string())]);
+    assert_eq!(kinds("(fn g [] (h)) (fn k [] (g)) (k) (fn h [] 1)"), vec![DiagnosticKind::UsedBeforeDefinition("h".to_string())]);
+    assert!(kinds("(fn g [] (h)) (let later g) (fn h [] 1) (g) (later)").is_empty());
+    assert!(kinds("(fn f [] [(fn inner [] (helper)) (fn helper [] 1) (inner)])").is_empty());
+}
+
+#[test]
+fn builtin_arity(){
+    assert_eq!(
+        kinds("(nth 1) (car) (nth (list 1) :index 0) (nth 0 (list 1)) (+ 1 2 3)"),
+        vec![
+            DiagnosticKind::WrongArity{name: "nth".to_string(), min: 2, max: Some(2), actual: 1},
+            DiagnosticKind::WrongArity{name: "car".to_string(), min: 1, max: Some(1), actual: 0},
+        ]
+    );
+}
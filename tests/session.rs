@@ -0,0 +1,98 @@
+use std::rc::Rc;
+
+use kisp::assert_match;
+use kisp::interpreter::Interpreter;
+use kisp::testutils::{temp_path, text};
+use kisp::value::EvalValue;
+use kisp::value::native::NativeType;
+use kisp::value::numeric::Numeric;
+
+#[test]
+fn save_and_load_round_trip(){
+    let interpreter = Interpreter::new();
+    interpreter.eval_str("
+        (let greeting \"hi \\\"there\\\"\")
+        (let numbers (list 1 2.5 :k (list)))
+        (fn greet [name (punct \"!\") :times 1 & rest] (format \"{} {}{} {}\" greeting name punct times))
+        (let twice (lambda [x] (* 2 x)))
+        (let show print)
+    ").unwrap();
+    let path = temp_path("session", ".kisp");
+    interpreter.save_session(&path).unwrap();
+
+    let mut restored = Interpreter::new();
+    restored.load_session(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(text(&restored, "(greet \"bob\" :times 3)"), "hi \"there\" bob! 3");
+    assert_match!(restored.eval_str("(twice 21)"), Ok(EvalValue::Numeric(Numeric::Integer(42))));
+    assert_match!(restored.eval_str("(= numbers (list 1 2.5 :k (list)))"), Ok(EvalValue::True));
+    assert_match!(restored.eval_str("(show)"), Ok(EvalValue::Unit));
+}
+
+#[test]
+fn source_skips_host_values(){
+    let interpreter = Interpreter::new();
+    let kind = Rc::new(NativeType::new("Handle"));
+    interpreter.define("handle", EvalValue::native(&kind, 5u8));
+    interpreter.eval_str("(let answer 42)").unwrap();
+    assert_eq!(interpreter.session_source(), "(let answer 42)\n; handle skipped, a native can't be saved\n");
+}
+
+#[test]
+fn load_replaces_definitions(){
+    let path = temp_path("replace", ".kisp");
+    std::fs::write(&path, "(let kept 1)").unwrap();
+    let mut interpreter = Interpreter::new();
+    interpreter.eval_str("(let dropped 2)").unwrap();
+    interpreter.load_session(&path).unwrap();
+    assert!(interpreter.get("dropped").is_none());
+    assert!(interpreter.get("kept").is_some());
+
+    //a broken file leaves the session as it was
+    std::fs::write(&path, "(let other 3) (undefined)").unwrap();
+    assert!(interpreter.load_session(&path).is_err());
+    std::fs::remove_file(&path).unwrap();
+    assert!(interpreter.get("kept").is_some());
+    assert!(interpreter.get("other").is_none());
+}
+
+fn round_trip(interpreter: &Interpreter, name: &str) -> Interpreter {
+    let path = temp_path(name, ".kisp");
+    interpreter.save_session(&path).unwrap();
+    let mut restored = Interpreter::new();
+    let loaded = restored.load_session(&path);
+    std::fs::remove_file(&path).unwrap();
+    loaded.unwrap();
+    restored
+}
+
+#[test]
+fn aliases_load_after_their_targets(){
+    let interpreter = Interpreter::new();
+    interpreter.eval_str("(fn zeta [] 1) (let alpha zeta)").unwrap();
+    assert_eq!(interpreter.session_source(), "(fn zeta [] 1)\n(let alpha zeta)\n");
+    let restored = round_trip(&interpreter, "alias");
+    assert_match!(restored.eval_str("(alpha)"), Ok(EvalValue::Numeric(Numeric::Integer(1))));
+}
+
+#[test]
+fn returned_functions_are_skipped(){
+    let interpreter = Interpreter::new();
+    interpreter.eval_str("(fn outer [] (fn inner [] 2) inner) (let g (outer))").unwrap();
+    let restored = round_trip(&interpreter, "returned");
+    assert!(restored.get("g").is_none());
+    assert!(interpreter.session_source().contains("; g skipped"));
+    assert_match!(restored.eval_str("((outer))"), Ok(EvalValue::Numeric(Numeric::Integer(2))));
+}
+
+#[test]
+fn closures_over_locals_are_skipped(){
+    let interpreter = Interpreter::new();
+    interpreter.eval_str("(fn adder [n] (lambda [x] (+ x n))) (let add5 (adder 5)) (fn f [] 1) (let old f) (fn f [] 2)").unwrap();
+    let source = interpreter.session_source();
+    assert!(source.contains("; add5 skipped"), "{}", source);
+    assert!(source.contains("; old skipped"), "{}", source);
+    let restored = round_trip(&interpreter, "closure");
+    assert!(restored.get("add5").is_none());
+    assert_match!(restored.eval_str("(f)"), Ok(EvalValue::Numeric(Numeric::Integer(2))));
+}
@@ -0,0 +1,70 @@
+use kisp::assert_match;
+use kisp::interpreter::Interpreter;
+use kisp::testutils::run_script;
+use kisp::value::EvalValue;
+
+//runs the script and returns what was traced to stderr
+fn traced(name: &str, flags: &[&str], source: &str) -> String {
+    let output = run_script(env!("CARGO_BIN_EXE_kisp"), &format!("trace-{}", name), flags, source);
+    String::from_utf8(output.stderr).unwrap()
+}
+
+#[test]
+fn trace_flag_covers_lambdas_and_calls_from_builtins(){
+    let source = "(fn square [x] (* x x))\n(fn sum [xs] (fold 0 (lambda [a b] (+ a (square b))) xs))\n(sum (list 1 2))";
+    assert_eq!(traced("flag", &["--trace"], source), "\
+(sum (list 1 2))
+  (lambda@2:36 0 1)
+    (square 1)
+    square => 1
+  lambda@2:36 => 1
+  (lambda@2:36 1 2)
+    (square 2)
+    square => 4
+  lambda@2:36 => 5
+sum => 5
+");
+}
+
+#[test]
+fn trace_selected_functions(){
+    let source = "\
+(fn square [x] (* x x))
+(fn double [x] (* x 2))
+(trace square)
+(map (lambda [x] (double (square x))) (list 2))
+(untrace square)
+(square 3)";
+    //called from inside the lambda, one level down
+    assert_eq!(traced("selected", &[], source), "  (square 2)\n  square => 4\n");
+}
+
+#[test]
+fn errors_and_tail_calls(){
+    let source = "\
+(fn count [n] (if (= n 0) :done (count (- n 1))))
+(fn broken [s] (car s))
+(trace count)
+(trace broken)
+(count 2)
+(try (broken \"text\"))";
+    assert_eq!(traced("errors", &[], source), "\
+(count 2)
+  (count 1)
+  (count 0)
+count => :done
+(broken \"text\")
+broken failed: expected list, found string
+");
+}
+
+#[test]
+fn only_functions_can_be_traced(){
+    let interpreter = Interpreter::new();
+    assert!(interpreter.eval_str("(trace car)").is_err());
+    assert!(interpreter.eval_str("(trace 1)").is_err());
+    interpreter.eval_str("(fn f [] 1)").unwrap();
+    assert_match!(interpreter.eval_str("(untrace f)"), Ok(EvalValue::Unit));
+    interpreter.eval_str("(trace f)").unwrap();
+    assert_match!(interpreter.eval_str("(untrace f)"), Ok(EvalValue::True));
+}